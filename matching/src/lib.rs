@@ -0,0 +1,121 @@
+//! The wildcard path matching of [Texting Robots](https://crates.io/crates/texting_robots)
+//! for `no_std` targets.
+//!
+//! `robots.txt` rules are matched against a URL's path as a literal prefix where
+//! `*` matches any run of bytes and a trailing `$` anchors the rule to the end
+//! of the path. Texting Robots compiles some rules to a regular expression but
+//! every rule can be matched with the functions here, which need neither `std`
+//! nor `alloc`.
+//!
+//! Patterns are expected to be normalized and percent encoded as by Texting
+//! Robots (i.e. no runs of `*`) and `$` isn't interpreted: strip it and use
+//! [match_stars_anchored] for an anchored rule.
+//!
+//! ```rust
+//! use texting_robots_matching::{match_stars, match_stars_anchored};
+//!
+//! assert!(match_stars(b"/fish*.php", b"/fish/salmon.php?q=1"));
+//! assert!(!match_stars(b"/fish*.php", b"/shark.php"));
+//! assert!(match_stars_anchored(b"/*.php", b"/fish.php"));
+//! assert!(!match_stars_anchored(b"/*.php", b"/fish.php?q=1"));
+//! ```
+#![no_std]
+
+use memchr::memmem;
+
+#[cfg(test)]
+mod test;
+
+/// The result of matching a pattern with at most a limited amount of work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StarMatch {
+    /// The pattern matched the text until this byte offset.
+    Matched(usize),
+    /// The pattern doesn't match the text.
+    NotMatched,
+    /// More bytes of the text than the limit were scanned before deciding.
+    LimitExceeded,
+}
+
+/// Check the text starts with a match of the pattern where each `*` matches
+/// any run of bytes.
+pub fn match_stars(pattern: &[u8], text: &[u8]) -> bool {
+    matches!(
+        match_stars_within(pattern, text, usize::MAX),
+        StarMatch::Matched(_)
+    )
+}
+
+/// As [match_stars] but the pattern must match until the end of the text.
+pub fn match_stars_anchored(pattern: &[u8], text: &[u8]) -> bool {
+    matches!(
+        match_stars_anchored_within(pattern, text, usize::MAX),
+        StarMatch::Matched(_)
+    )
+}
+
+/// As [match_stars] giving where the match ends and scanning at most
+/// `scan_limit` bytes of the text.
+///
+/// Each part of the pattern between the `*` is found with a single linear
+/// search from where the last ended, so the work is bounded by the length of
+/// the text and the limit only matters for adversarially long URLs.
+pub fn match_stars_within(
+    pattern: &[u8],
+    text: &[u8],
+    scan_limit: usize,
+) -> StarMatch {
+    // Break the pattern into the parts between the "*"
+    let mut parts = pattern.split(|&b| b == b'*');
+
+    // The first part must match at the very start
+    // If the pattern starts with a '*' the first part is empty
+    let first = parts.next().unwrap_or_default();
+    if !text.starts_with(first) {
+        return StarMatch::NotMatched;
+    }
+    let mut starting_point = first.len();
+    // Bytes of the text scanned so far (see scan_limit)
+    let mut scanned: usize = 0;
+
+    for part in parts {
+        let rest = &text[starting_point..];
+        let found = memmem::find(rest, part);
+        let end = found.map_or(rest.len(), |pos| pos + part.len());
+        scanned = scanned.saturating_add(end);
+        if scanned > scan_limit {
+            return StarMatch::LimitExceeded;
+        }
+        match found {
+            Some(pos) => starting_point += pos + part.len(),
+            None => return StarMatch::NotMatched,
+        }
+    }
+
+    StarMatch::Matched(starting_point)
+}
+
+/// As [match_stars_anchored] scanning at most `scan_limit` bytes of the text
+/// (see [match_stars_within]).
+pub fn match_stars_anchored_within(
+    pattern: &[u8],
+    text: &[u8],
+    scan_limit: usize,
+) -> StarMatch {
+    // The part after the last "*" must be at the very end of the text
+    // The rest of the pattern then matches within what remains before it
+    let matched = match pattern.iter().rposition(|&b| b == b'*') {
+        Some(idx) => match text.strip_suffix(&pattern[idx + 1..]) {
+            Some(rest) => {
+                match_stars_within(&pattern[..idx], rest, scan_limit)
+            }
+            None => StarMatch::NotMatched,
+        },
+        None if text == pattern => StarMatch::Matched(text.len()),
+        None => StarMatch::NotMatched,
+    };
+    match matched {
+        StarMatch::Matched(_) => StarMatch::Matched(text.len()),
+        other => other,
+    }
+}
@@ -0,0 +1,47 @@
+use super::{
+    match_stars, match_stars_anchored, match_stars_anchored_within,
+    match_stars_within, StarMatch,
+};
+
+#[test]
+fn test_match_stars() {
+    assert!(match_stars(b"/fish", b"/fish/salmon"));
+    assert!(!match_stars(b"/fish", b"/fis"));
+    assert!(match_stars(b"*", b""));
+    assert!(match_stars(b"/*/salmon", b"/fish/salmon"));
+    assert!(!match_stars(b"/*/salmon", b"/salmon"));
+    assert!(match_stars(b"*a*a", b"/aa"));
+    assert!(!match_stars(b"*a*a", b"/a"));
+    assert_eq!(
+        match_stars_within(b"/fish*.php", b"/fish/a.php?q", usize::MAX),
+        StarMatch::Matched(11)
+    );
+}
+
+#[test]
+fn test_match_stars_anchored() {
+    assert!(match_stars_anchored(b"/fish", b"/fish"));
+    assert!(!match_stars_anchored(b"/fish", b"/fish/"));
+    assert!(match_stars_anchored(b"/*.php", b"/fish.php"));
+    assert!(!match_stars_anchored(b"/*.php", b"/fish.php?q"));
+    // The suffix can't overlap the prefix
+    assert!(!match_stars_anchored(b"/a*a", b"/a"));
+    assert_eq!(
+        match_stars_anchored_within(b"/*.php", b"/fish.php", usize::MAX),
+        StarMatch::Matched(9)
+    );
+}
+
+#[test]
+fn test_match_stars_scan_limit() {
+    let text = [b'a'; 1000];
+    assert_eq!(
+        match_stars_within(b"*b", &text, 100),
+        StarMatch::LimitExceeded
+    );
+    assert_eq!(match_stars_within(b"*b", &text, 1000), StarMatch::NotMatched);
+    assert_eq!(
+        match_stars_anchored_within(b"*b*a", &text, 100),
+        StarMatch::LimitExceeded
+    );
+}
@@ -0,0 +1,42 @@
+use encoding_rs::Encoding;
+
+use crate::Robot;
+
+impl Robot {
+    /// Construct a new Robot as in [Robot::new] for a `robots.txt` served in
+    /// the given charset (i.e. from the `Content-Type` header).
+    ///
+    /// The input is transcoded to UTF-8 before parsing so paths and sitemaps
+    /// in encodings such as Latin-1 or Shift_JIS survive. Charset labels are
+    /// those of the [WHATWG Encoding Standard](https://encoding.spec.whatwg.org/)
+    /// and a byte order mark takes precedence over the label. As servers often
+    /// send bogus labels an unknown charset is treated as [Robot::new] would.
+    ///
+    /// Requires the `charset` feature.
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"Disallow: /caf\xe9\nSitemap: https://example.com/caf\xe9.xml";
+    /// let r = Robot::new_with_charset("Ferris", txt, "ISO-8859-1").unwrap();
+    /// assert_eq!(r.allowed("/café"), false);
+    /// assert_eq!(r.sitemaps, ["https://example.com/café.xml"]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [Robot::new].
+    pub fn new_with_charset(
+        agent: &str,
+        txt: &[u8],
+        charset: &str,
+    ) -> Result<Self, anyhow::Error> {
+        match Encoding::for_label(charset.trim().as_bytes()) {
+            Some(encoding) => {
+                let (txt, _, _) = encoding.decode(txt);
+                Robot::new(agent, txt.as_bytes())
+            }
+            None => Robot::new(agent, txt),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::Robot;
+
+// Escape a label for use inside a double quoted DOT string
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Robot {
+    /// Export the rules as a [Graphviz](https://graphviz.org/) DOT graph.
+    ///
+    /// Each rule is a node labelled with its directive and pattern, colored
+    /// green for Allow and red for Disallow. Nodes are chained in order of
+    /// precedence with the first matching rule in the chain deciding a URL.
+    ///
+    /// Requires the `dot` feature.
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /\nAllow: /ocean").unwrap();
+    /// let dot = r.to_dot();
+    /// assert!(dot.starts_with("digraph robots {"));
+    /// assert!(dot.contains("Allow: /ocean"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let rules = self.rules_by_precedence();
+        let mut dot = String::from("digraph robots {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [shape=box, style=filled];\n");
+        for (idx, rule) in rules.iter().enumerate() {
+            let (directive, color) = match rule.allowed {
+                true => ("Allow", "palegreen"),
+                false => ("Disallow", "lightcoral"),
+            };
+            dot.push_str(&format!(
+                "    r{} [label=\"{}: {}\", fillcolor={}];\n",
+                idx,
+                directive,
+                escape(rule.regex.pattern()),
+                color
+            ));
+        }
+        for idx in 1..rules.len() {
+            dot.push_str(&format!("    r{} -> r{};\n", idx - 1, idx));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
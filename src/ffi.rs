@@ -0,0 +1,107 @@
+//! A C API over [Robot] for use from other languages.
+//!
+//! Build a C compatible library with the `ffi` feature, i.e.
+//! `cargo rustc --release --features ffi --crate-type cdylib`, and declare:
+//!
+//! ```c
+//! typedef struct Robot Robot;
+//!
+//! Robot *robot_new(const char *agent, const uint8_t *txt, size_t len);
+//! bool robot_allowed(const Robot *robot, const char *url);
+//! bool robot_delay(const Robot *robot, float *delay);
+//! void robot_free(Robot *robot);
+//! ```
+use core::ffi::{c_char, CStr};
+use core::ptr;
+use core::slice;
+
+use crate::Robot;
+
+/// Construct a Robot for `agent` from the `len` bytes of `robots.txt` at `txt`.
+///
+/// Returns null if an argument is null or invalid, or if parsing fails as in
+/// [Robot::new]. A returned Robot must be released with [robot_free].
+///
+/// # Safety
+///
+/// `agent` must be a nul terminated string and `txt` must be valid for reads
+/// of `len` bytes (or may be null if `len` is zero).
+#[no_mangle]
+pub unsafe extern "C" fn robot_new(
+    agent: *const c_char,
+    txt: *const u8,
+    len: usize,
+) -> *mut Robot {
+    if agent.is_null() || (txt.is_null() && len > 0) {
+        return ptr::null_mut();
+    }
+    let agent = match CStr::from_ptr(agent).to_str() {
+        Ok(agent) => agent,
+        Err(_) => return ptr::null_mut(),
+    };
+    let txt = match len {
+        0 => &[],
+        _ => slice::from_raw_parts(txt, len),
+    };
+    match Robot::new(agent, txt) {
+        Ok(robot) => Box::into_raw(Box::new(robot)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Check if `url` is allowed as in [Robot::allowed].
+///
+/// Returns false if either argument is null. Invalid UTF-8 in the URL is
+/// replaced before checking.
+///
+/// # Safety
+///
+/// `robot` must come from [robot_new] and not yet be freed and `url` must be
+/// a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn robot_allowed(
+    robot: *const Robot,
+    url: *const c_char,
+) -> bool {
+    if robot.is_null() || url.is_null() {
+        return false;
+    }
+    (*robot).allowed(&CStr::from_ptr(url).to_string_lossy())
+}
+
+/// Write the crawl delay in seconds to `delay` returning true if one is set.
+///
+/// Returns false without writing if there's no delay or an argument is null.
+///
+/// # Safety
+///
+/// `robot` must come from [robot_new] and not yet be freed and `delay` must
+/// be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn robot_delay(
+    robot: *const Robot,
+    delay: *mut f32,
+) -> bool {
+    if robot.is_null() || delay.is_null() {
+        return false;
+    }
+    match (*robot).delay {
+        Some(d) => {
+            *delay = d;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Release a Robot constructed by [robot_new]. Null is ignored.
+///
+/// # Safety
+///
+/// `robot` must come from [robot_new] and not already be freed.
+#[no_mangle]
+pub unsafe extern "C" fn robot_free(robot: *mut Robot) {
+    if !robot.is_null() {
+        drop(Box::from_raw(robot));
+    }
+}
@@ -89,7 +89,7 @@ the [suggestions made by Google are recommended][google-spec].
 - 3xx (redirection): Follow a reasonable number of redirects
 - 4xx (client error): Assume there are no crawl restrictions except for:
   - 429 "Too Many Requests": Retry after a reasonable amount of time
-  (potentially set by the "[Retry-After][mozilla-ra]" header)
+    (potentially set by the "[Retry-After][mozilla-ra]" header)
 - 5xx (server errors): Assume you should not crawl until fixed and/or interpret with care
 
 Even when directed to "assume no crawl restrictions" it is likely reasonable and
@@ -124,10 +124,9 @@ that host thousands or millions of domains. How you rate limit fairly using the
 `Crawl-Delay` is entirely up to the end user (and potentially the service when
 using HTTP error code 429 to rate limit traffic).
 
-To protect against adverse input the user of Texting Robots is also suggested to
-follow [Google's recommendations][google-spec] and limit input to 500 kibibytes.
-This is not yet done at the library level in case a larger input may be desired
-but may be revisited depending on community feedback.
+To protect against adverse input Texting Robots follows
+[Google's recommendations][google-spec] and ignores input past 500 kibibytes.
+If a larger input is desired the limit can be raised with `Robot::new_with_limit`.
 
 [mozilla-tmr]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/429
 [mozilla-ra]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Retry-After
@@ -139,9 +138,14 @@ library was designed to support language integrations in the future. Battle
 testing this intepretation of the `robots.txt` specification against the web is
 easier done testing with friends!
 
-A C API through Rust FFI should be relatively easy to provide given Texting Robots
-only relies on strings, floats, and booleans. The lack of native fetching abilities
-should ensure the library is portable across platforms, situations, and languages.
+A C API through Rust FFI is provided by the `ffi` feature given Texting Robots
+only relies on strings, floats, and booleans (see the `ffi` module). The lack of
+native fetching abilities should ensure the library is portable across platforms,
+situations, and languages.
+
+The library itself requires `std` but the wildcard path matching is available
+for `no_std` targets, without even `alloc`, from the `texting_robots_matching`
+crate in the `matching` directory.
 
 A proof of concept was performed in [WASI][wasi], the "WebAssembly System Interface",
 showing that the library compiles happily and only experiences a 50% or 75% speed
@@ -224,17 +228,24 @@ cargo tarpaulin --ignore-tests -v
 
 */
 
+use core::cmp::Reverse;
 use core::fmt;
+use core::time::Duration;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 
 use bstr::ByteSlice;
 
 use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use regex::{RegexSet, RegexSetBuilder};
 
 use thiserror::Error;
-use url::{ParseError, Position, Url};
+use url::{Host, ParseError, Position, Url};
 
 mod minregex;
-use minregex::MinRegex as RobotRegex;
+use minregex::{
+    MinRegex as RobotRegex, DEFAULT_MATCH_SCAN_LIMIT, DEFAULT_REGEX_SIZE_LIMIT,
+};
 
 #[cfg(test)]
 mod test;
@@ -245,29 +256,591 @@ mod test_repcpp;
 #[cfg(test)]
 mod test_get_robots_url;
 
+#[cfg(test)]
+mod test_fetch_policy;
+
+#[cfg(test)]
+mod test_stream;
+
+#[cfg(test)]
+mod test_minregex;
+
+#[cfg(all(test, feature = "dot"))]
+mod test_dot;
+
+#[cfg(all(test, feature = "serde"))]
+mod test_serialize;
+
+#[cfg(all(test, feature = "charset"))]
+mod test_charset;
+
+#[cfg(all(test, feature = "ffi"))]
+mod test_ffi;
+
+#[cfg(feature = "charset")]
+mod charset;
+
+#[cfg(feature = "dot")]
+mod dot;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+mod policy;
+pub use policy::{RobotsFetchPolicy, StatusAction};
+
+mod stream;
+pub use stream::RobotParser;
+
 mod parser;
-use crate::parser::{robots_txt_parse, Line};
+pub use crate::parser::Line;
+use crate::parser::{
+    directive_keyword, robots_txt_parse, robots_txt_parse_with_raw,
+    rule_with_literal_hash,
+};
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
+    /// Previously returned on any parsing error and now replaced by
+    /// [Parse](Error::Parse). This is never returned.
+    #[deprecated(note = "use Error::Parse")]
+    #[error("Failed to parse robots.txt")]
+    InvalidRobots,
     /// On any parsing error encountered parsing `robots.txt` this error will
     /// be returned.
     ///
     /// Note: Parsing errors should be rare as the parser is highly forgiving.
     #[error("Failed to parse robots.txt")]
-    InvalidRobots,
+    Parse,
+    /// The rule couldn't be compiled for matching.
+    ///
+    /// This is only returned for rules given directly (i.e. [Robot::from_rules]).
+    /// Such rules found in `robots.txt` are instead skipped and reported as a
+    /// [RuleTooComplex](WarningKind::RuleTooComplex) warning.
+    ///
+    /// Note: Rules exceeding [RobotBuilder::regex_size_limit] aren't errors
+    /// as they're matched without compiling a regex.
+    #[error("Rule too complex to compile: {pattern}")]
+    RuleTooComplex {
+        /// The rule that couldn't be compiled.
+        pattern: String,
+    },
 }
 
 fn percent_encode(input: &str) -> String {
     // Paths outside ASCII must be percent encoded
     const FRAGMENT: &AsciiSet =
         &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
-    utf8_percent_encode(input, FRAGMENT).to_string()
+    uppercase_percent_hex(utf8_percent_encode(input, FRAGMENT).to_string())
+}
+
+// Canonicalize the hex digits of percent encoded octets to uppercase as Google does
+// i.e. "%aa" is equivalent to "%AA" though the octet itself is never decoded
+fn uppercase_percent_hex(input: String) -> String {
+    if !input.contains('%') {
+        return input;
+    }
+    let mut bytes = input.into_bytes();
+    for idx in 0..bytes.len().saturating_sub(2) {
+        if bytes[idx] == b'%'
+            && bytes[idx + 1].is_ascii_hexdigit()
+            && bytes[idx + 2].is_ascii_hexdigit()
+        {
+            bytes[idx + 1].make_ascii_uppercase();
+            bytes[idx + 2].make_ascii_uppercase();
+        }
+    }
+    // Only ASCII hex digits were changed so this remains valid UTF-8
+    String::from_utf8(bytes).unwrap()
+}
+
+// Decode percent encoded unreserved characters as defined in RFC 3986
+// i.e. "%41" is equivalent to "A" but "%2F" isn't equivalent to "/"
+fn decode_unreserved(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let hex = bytes.get(idx + 1..idx + 3).filter(|hex| {
+            bytes[idx] == b'%' && hex.iter().all(|c| c.is_ascii_hexdigit())
+        });
+        let octet = hex.and_then(|hex| {
+            u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+        });
+        match octet {
+            Some(c) if c.is_ascii_alphanumeric() || b"-._~".contains(&c) => {
+                decoded.push(c);
+                idx += 3;
+            }
+            _ => {
+                decoded.push(bytes[idx]);
+                idx += 1;
+            }
+        }
+    }
+    // Only ASCII triplets were replaced by ASCII so this remains valid UTF-8
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Remove any query parameters from the path + query whose name matches
+fn strip_query_params(url: &str, strip: impl Fn(&str) -> bool) -> String {
+    let (url, fragment) = match url.find('#') {
+        Some(idx) => url.split_at(idx),
+        None => (url, ""),
+    };
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => return format!("{}{}", url, fragment),
+    };
+    let query: Vec<&str> = query
+        .split('&')
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or(param);
+            !param.is_empty() && !strip(name)
+        })
+        .collect();
+    if query.is_empty() {
+        format!("{}{}", path, fragment)
+    } else {
+        format!("{}?{}{}", path, query.join("&"), fragment)
+    }
+}
+
+// Normalize the scheme, host, and port of a URL for comparing origins
+// The host is compared case insensitively and ignoring a trailing dot
+fn origin_key(url: &Url) -> Option<(&str, String, u16)> {
+    let host = url.host_str()?;
+    let host = host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase();
+    Some((url.scheme(), host, url.port_or_known_default()?))
+}
+
+// Check if the URL belongs to the origin, with relative URLs assumed to do so
+fn is_same_origin(origin: &Url, url: &str) -> bool {
+    match Url::parse(url) {
+        Ok(url) => {
+            let key = origin_key(&url);
+            key.is_some() && key == origin_key(origin)
+        }
+        Err(_) => true,
+    }
+}
+
+// A Host directive names the preferred mirror as a hostname with an optional
+// scheme and port (i.e. "example.com" or "https://example.com:8080")
+fn is_valid_host(host: &str) -> bool {
+    let url = match host.contains("://") {
+        true => Url::parse(host),
+        false => Url::parse(&format!("http://{}", host)),
+    };
+    let url = match url {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    let valid_domain = match url.host() {
+        Some(url::Host::Domain(domain)) => domain.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|c| c.is_ascii_alphanumeric() || c == b'-')
+        }),
+        Some(_) => true,
+        None => false,
+    };
+    valid_domain
+        && matches!(url.scheme(), "http" | "https")
+        && url.username().is_empty()
+        && url.password().is_none()
+        && url.path() == "/"
+        && url.query().is_none()
+        && url.fragment().is_none()
+}
+
+// Split a User-Agent line listing several agents (i.e. "Googlebot, Bingbot")
+fn agent_tokens(ua: &[u8]) -> impl Iterator<Item = &[u8]> {
+    ua.split(|&c| c == b',')
+        .map(|token| token.trim())
+        .filter(|t| !t.is_empty())
+}
+
+// Collect only the lines relevant to the (lowercased) user agent
+// If the agent isn't referenced we use the catch all agent ("*")
+// Lines are given with their line number which is kept in the result
+// The User-Agent token as written in the file that matched is also returned
+fn group_for_agent<'a>(
+    agent: &str,
+    lines: &[(usize, Line<'a>)],
+) -> (Vec<(usize, Line<'a>)>, Option<&'a [u8]>) {
+    // The most specific group is the longest User-Agent that's a prefix of our agent
+    // (i.e. "Googlebot-News" uses "Googlebot" if there's no "Googlebot-News" group)
+    // The prefix must end at a token boundary so "a" doesn't capture "applebot"
+    // Ties go to the first in the file and we fall back to the catch all agent ("*")
+    let agent = agent.as_bytes();
+    let mut best: Option<&[u8]> = None;
+    for (_, line) in lines {
+        if let Line::UserAgent(ua) = line {
+            for ua in agent_tokens(ua) {
+                // Compare without allocating a lowercased copy of every token
+                let is_prefix = ua != b"*"
+                    && agent.len() >= ua.len()
+                    && agent[..ua.len()].eq_ignore_ascii_case(ua)
+                    && matches!(
+                        agent.get(ua.len()),
+                        None | Some(b'-' | b'_' | b'/' | b' ' | b'\t')
+                    );
+                if is_prefix && best.is_none_or(|b| ua.len() > b.len()) {
+                    best = Some(ua);
+                }
+            }
+        }
+    }
+    let agent = best.unwrap_or(b"*");
+
+    // Collect only the lines relevant to this user agent
+    // If there are no User-Agent lines then we capture all
+    let mut capturing = false;
+    if lines.iter().filter(|(_, x)| matches!(x, Line::UserAgent(_))).count()
+        == 0
+    {
+        capturing = true;
+    }
+    let mut subset = vec![];
+    let mut matched = None;
+    let mut idx: usize = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+
+        // User-Agents can be given in blocks with rules applicable to all User-Agents in the block
+        // On a new block of User-Agents we're either in it or no longer active
+        if let (_, Line::UserAgent(_)) = line {
+            capturing = false;
+            // Lines that don't end the block apply to every agent in it
+            let mut within = vec![];
+            while idx < lines.len() {
+                match lines[idx] {
+                    (_, Line::UserAgent(ua)) => {
+                        if let Some(ua) = agent_tokens(ua)
+                            .find(|ua| ua.eq_ignore_ascii_case(agent))
+                        {
+                            capturing = true;
+                            matched = matched.or(Some(ua));
+                        }
+                    }
+                    line if within_agent_block(&line.1) => within.push(line),
+                    _ => break,
+                }
+                idx += 1;
+            }
+            if capturing {
+                subset.extend(within);
+            }
+            continue;
+        }
+
+        if capturing {
+            subset.push(line);
+        }
+        idx += 1;
+    }
+    (subset, matched)
+}
+
+// Whether the line may appear between User-Agent lines without ending the block
+// (i.e. "User-Agent: a\nRequest-rate: 1/5\nUser-Agent: b" is a single group)
+fn within_agent_block(line: &Line) -> bool {
+    matches!(
+        line,
+        Line::RequestRate(_) | Line::VisitTime { .. } | Line::Noindex(_)
+    )
+}
+
+// Find the crawl delay given the agent's lines and all lines
+fn crawl_delay(
+    subset: &[(usize, Line)],
+    lines: &[(usize, Line)],
+) -> Option<f32> {
+    // Collect the crawl delay
+    let mut delay = subset
+        .iter()
+        .filter_map(|(_, x)| match x {
+            Line::CrawlDelay(Some(d)) => Some(d),
+            _ => None,
+        })
+        .copied()
+        .next();
+
+    // Special note for crawl delay:
+    // Some robots.txt files have it at the top, before any User-Agent lines, to apply to all
+    if delay.is_none() {
+        for (_, line) in lines.iter() {
+            if let Line::CrawlDelay(Some(d)) = line {
+                delay = Some(*d);
+            }
+            if let Line::UserAgent(_) = line {
+                break;
+            }
+        }
+    }
+    delay
+}
+
+// The directory of an index page (i.e. "/a/" for "/a/index.html")
+fn index_directory(path: &str) -> Option<&str> {
+    let directory = path
+        .strip_suffix("index.html")
+        .or_else(|| path.strip_suffix("index.htm"))?;
+    Some(directory).filter(|directory| directory.ends_with('/'))
+}
+
+// Number the lines (starting from 1) keeping only those relevant to finding a delay
+fn delay_lines(lines: Vec<Line>) -> Vec<(usize, Line)> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(idx, x)| (idx + 1, x))
+        .filter(|(_, x)| {
+            !matches!(
+                x,
+                Line::Sitemap(_)
+                    | Line::Host(_)
+                    | Line::CleanParam { .. }
+                    | Line::Raw(_)
+            ) && !within_agent_block(x)
+        })
+        .collect()
+}
+
+// Prepare the pattern of an Allow or Disallow rule, skipping it if not UTF-8 or empty
+fn trim_rule(pat: &[u8]) -> Option<&str> {
+    // A stray BOM or control characters around the rule can't appear in a URL
+    // as the URL parser strips them, so they're removed here too
+    // Any control characters within the rule are percent encoded as in a URL
+    let pat = pat.to_str().ok()?.trim_matches(|c: char| {
+        c == '\u{feff}' || (c.is_ascii_control() && c != '\t')
+    });
+    Some(pat).filter(|pat| !pat.is_empty())
+}
+
+// Keep only the complete lines within the first `max` bytes
+// Everything past the limit is ignored as Google does rather than erroring
+// As a line ending is ASCII this never splits a UTF-8 sequence
+fn truncate_input(txt: &[u8], max: usize) -> &[u8] {
+    if txt.len() <= max {
+        return txt;
+    }
+    match txt[..max].iter().rposition(|x| matches!(x, b'\n' | b'\r' | 0)) {
+        Some(idx) => &txt[..=idx],
+        None => &[],
+    }
+}
+
+// The line number editors show for each parsed line of the (unreplaced) input
+// Parsing ends a line at a null (see replace_nulls) but it isn't counted here
+fn editor_line_numbers(
+    original: &[u8],
+    raw_lines: &[(&[u8], Line)],
+    unparsed: usize,
+) -> Vec<usize> {
+    // The parsed lines are contiguous and follow any BOM
+    let parsed: usize = raw_lines.iter().map(|(raw, _)| raw.len()).sum();
+    let mut offset = original.len() - unparsed - parsed;
+    let mut number = 1;
+    raw_lines
+        .iter()
+        .map(|(raw, _)| {
+            let current = number;
+            offset += raw.len();
+            if original.get(offset.wrapping_sub(1)) != Some(&0) {
+                number += 1;
+            }
+            current
+        })
+        .collect()
+}
+
+// Replace '\x00' with '\n'
+// This shouldn't be necessary but some websites are strange ...
+fn replace_nulls(txt: &[u8]) -> Vec<u8> {
+    txt.iter().map(|x| if *x == 0 { b'\n' } else { *x }).collect()
+}
+
+/// A group of rules from `robots.txt` applying to the listed user agents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    /// The User-Agent tokens as written in `robots.txt`.
+    pub agents: Vec<String>,
+    /// The rules as `(pattern, is_allowed)` with patterns as written.
+    pub rules: Vec<(String, bool)>,
+    /// The first valid `Crawl-Delay` in the group.
+    pub crawl_delay: Option<f32>,
+}
+
+/// Tokenize `robots.txt` into [Line]s without any of the interpretation done
+/// by [Robot] (i.e. agent selection or rule precedence).
+///
+/// The lines borrow from `txt` which is used as given, so unlike [Robot::new]
+/// no input limit is applied. A leading BOM is skipped.
+///
+/// # Errors
+///
+/// If there are difficulties parsing an [Parse](Error::Parse) error is
+/// returned as in [Robot::new].
+///
+/// # Example
+///
+/// ```rust
+/// use texting_robots::{parse_lines, Line};
+///
+/// let lines = parse_lines(b"User-Agent: Ferris\nDisallow: /secret # shh").unwrap();
+/// assert_eq!(lines[0], Line::UserAgent(b"Ferris"));
+/// assert_eq!(lines[1], Line::Disallow(b"/secret"));
+/// ```
+pub fn parse_lines(txt: &[u8]) -> Result<Vec<Line<'_>>, Error> {
+    let (_, lines) = robots_txt_parse(txt).map_err(|_| Error::Parse)?;
+    Ok(lines)
+}
+
+/// Parse every User-Agent group in `robots.txt` without selecting an agent.
+///
+/// Lines are grouped as they are by [Robot::new] with consecutive User-Agent
+/// lines sharing the rules that follow. If there are no User-Agent lines all
+/// rules form a single group for `*`. Any lines before the first User-Agent
+/// otherwise belong to no group.
+///
+/// Sitemaps aren't tied to any group and are available from [Robot::sitemaps]
+/// for any agent.
+///
+/// # Errors
+///
+/// If there are difficulties parsing an [Parse](Error::Parse)
+/// error is returned as in [Robot::new].
+///
+/// ```rust
+/// use texting_robots::parse_groups;
+///
+/// let txt = b"User-Agent: A\nUser-Agent: B\nDisallow: /ab\nUser-Agent: *\nAllow: /";
+/// let groups = parse_groups(txt).unwrap();
+/// assert_eq!(groups[0].agents, vec!["A", "B"]);
+/// assert_eq!(groups[0].rules, vec![("/ab".to_string(), false)]);
+/// assert_eq!(groups[1].agents, vec!["*"]);
+/// ```
+pub fn parse_groups(txt: &[u8]) -> Result<Vec<Group>, Error> {
+    let txt = replace_nulls(truncate_input(txt, DEFAULT_MAX_BYTES));
+    let (_, lines) = robots_txt_parse(&txt).map_err(|_| Error::Parse)?;
+
+    let empty = |agents| Group { agents, rules: vec![], crawl_delay: None };
+    let mut groups = vec![];
+    if !lines.iter().any(|x| matches!(x, Line::UserAgent(_))) {
+        groups.push(empty(vec!["*".to_string()]));
+    }
+    let mut in_agents = false;
+    for line in lines {
+        let group = match line {
+            Line::UserAgent(ua) => {
+                // Consecutive User-Agent lines form a single group
+                if !in_agents {
+                    groups.push(empty(vec![]));
+                    in_agents = true;
+                }
+                if let Some(group) = groups.last_mut() {
+                    group.agents.extend(
+                        agent_tokens(ua)
+                            .map(|ua| ua.to_str_lossy().into_owned()),
+                    );
+                }
+                continue;
+            }
+            // As in Robot::new these lines don't belong to any group
+            Line::Sitemap(_)
+            | Line::Host(_)
+            | Line::CleanParam { .. }
+            | Line::Raw(_) => continue,
+            ref line if within_agent_block(line) => continue,
+            _ => {
+                in_agents = false;
+                match groups.last_mut() {
+                    Some(group) => group,
+                    None => continue,
+                }
+            }
+        };
+        match line {
+            Line::Allow(pat) | Line::Disallow(pat) => {
+                let allowed = matches!(line, Line::Allow(_));
+                if let Some(pat) = trim_rule(pat) {
+                    group.rules.push((pat.to_string(), allowed));
+                }
+            }
+            Line::CrawlDelay(delay) => {
+                group.crawl_delay = group.crawl_delay.or(delay)
+            }
+            _ => {}
+        }
+    }
+    Ok(groups)
+}
+
+/// Find the crawl delay of every agent in `robots.txt` while parsing only once.
+///
+/// Each User-Agent token as written in `robots.txt` maps to the delay that
+/// [Robot::new] would give that agent, as does `*` for any agent not named.
+/// Agents without a delay are left out.
+///
+/// This avoids repeated parsing when delays are needed for many agents. For
+/// a single agent see [Robot::delay_for].
+///
+/// # Errors
+///
+/// If there are difficulties parsing an [Parse](Error::Parse)
+/// error is returned as in [Robot::new].
+///
+/// ```rust
+/// use texting_robots::crawl_delays;
+///
+/// let txt = b"User-Agent: A\nCrawl-Delay: 10\nUser-Agent: B\nDisallow: /\nUser-Agent: *\nCrawl-Delay: 1";
+/// let delays = crawl_delays(txt).unwrap();
+/// assert_eq!(delays["A"], 10.0);
+/// assert_eq!(delays["*"], 1.0);
+/// assert!(!delays.contains_key("B"));
+/// ```
+pub fn crawl_delays(txt: &[u8]) -> Result<HashMap<String, f32>, Error> {
+    let txt = replace_nulls(truncate_input(txt, DEFAULT_MAX_BYTES));
+    let (_, lines) = robots_txt_parse(&txt).map_err(|_| Error::Parse)?;
+    let lines = delay_lines(lines);
+
+    let mut agents: Vec<String> = vec!["*".to_string()];
+    for (_, line) in &lines {
+        if let Line::UserAgent(ua) = line {
+            for ua in agent_tokens(ua) {
+                let ua = ua.to_str_lossy();
+                if !agents.iter().any(|a| a.eq_ignore_ascii_case(&ua)) {
+                    agents.push(ua.into_owned());
+                }
+            }
+        }
+    }
+
+    let mut delays = HashMap::new();
+    for agent in agents {
+        let (subset, _) = group_for_agent(&agent.to_lowercase(), &lines);
+        if let Some(delay) = crawl_delay(&subset, &lines) {
+            delays.insert(agent, delay);
+        }
+    }
+    Ok(delays)
 }
 
 /// Construct the URL for `robots.txt` when given a base URL from the
 /// target domain.
 ///
+/// Internationalized hosts are given in their ASCII (punycode) form so the
+/// URL can be fetched directly (i.e. `http://例え.テスト/` becomes
+/// `http://xn--r8jz45g.xn--zckzah/robots.txt`). A trailing dot on the host is
+/// removed as `example.com.` and `example.com` are the same site.
+///
 /// # Errors
 ///
 /// If there are any issues in parsing the URL, a [ParseError][pe] from the
@@ -282,6 +855,32 @@ fn percent_encode(input: &str) -> String {
 ///
 /// [pe]: ParseError
 pub fn get_robots_url(url: &str) -> Result<String, ParseError> {
+    get_root_url(url, "/robots.txt")
+}
+
+/// Construct the URL for the conventional `sitemap.xml` when given a base URL
+/// from the target domain.
+///
+/// Crawlers commonly check this location when `robots.txt` lists no sitemaps.
+///
+/// # Errors
+///
+/// If there are any issues in parsing the URL, a [ParseError][pe] from the
+/// [URL crate](url) will be returned.
+///
+/// ```rust
+/// use texting_robots::get_sitemap_url;
+///
+/// let sitemap_url = get_sitemap_url("https://example.com/abc/file.html").unwrap();
+/// assert_eq!(sitemap_url, "https://example.com/sitemap.xml");
+/// ```
+///
+/// [pe]: ParseError
+pub fn get_sitemap_url(url: &str) -> Result<String, ParseError> {
+    get_root_url(url, "/sitemap.xml")
+}
+
+fn get_root_url(url: &str, path: &str) -> Result<String, ParseError> {
     let parsed = Url::parse(url);
     match parsed {
         Ok(mut url) => {
@@ -302,8 +901,20 @@ pub fn get_robots_url(url: &str) -> Result<String, ParseError> {
                 url.set_password(None).unwrap();
             }
 
-            match url.join("/robots.txt") {
-                Ok(robots_url) => Ok(robots_url.to_string()),
+            // A fully qualified host (i.e. "example.com.") is the same site
+            let stripped = match url.host() {
+                Some(Host::Domain(host)) => host
+                    .strip_suffix('.')
+                    .filter(|host| !host.is_empty())
+                    .map(str::to_string),
+                _ => None,
+            };
+            if let Some(host) = stripped {
+                url.set_host(Some(&host))?;
+            }
+
+            match url.join(path) {
+                Ok(root_url) => Ok(root_url.to_string()),
                 Err(e) => Err(e),
             }
         }
@@ -311,19 +922,310 @@ pub fn get_robots_url(url: &str) -> Result<String, ParseError> {
     }
 }
 
+/// Normalize a `robots.txt` rule pattern to the equivalent form used for matching.
+///
+/// Runs of `*` are collapsed, a trailing `*` or `*$` is removed as it allows any
+/// ending, and a rule of only a query string (i.e. `?ref=`) is given a leading
+/// `*` as it applies to any path. A lone `$` matches only the root (`/$`) while
+/// `*$` matches every path as `*` does.
+///
+/// ```rust
+/// use texting_robots::normalize_pattern;
+///
+/// assert_eq!(normalize_pattern("/x***y/"), "/x*y/");
+/// assert_eq!(normalize_pattern("/fish*$"), "/fish");
+/// assert_eq!(normalize_pattern("/*.php$"), "/*.php$");
+/// assert_eq!(normalize_pattern("$"), "/$");
+/// ```
+pub fn normalize_pattern(pattern: &str) -> String {
+    RobotRegex::normalize(pattern)
+}
+
+/// A preferred time window for crawling as given by the `Visit-time` directive.
+///
+/// Times are given as `(hour, minute)` in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisitTime {
+    pub start: (u8, u8),
+    pub end: (u8, u8),
+}
+
+impl VisitTime {
+    /// The window as `(start, end)` in minutes since midnight UTC.
+    ///
+    /// A window crossing midnight has an end before its start.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::VisitTime;
+    ///
+    /// let window = VisitTime { start: (6, 0), end: (8, 45) };
+    /// assert_eq!(window.as_minutes(), (360, 525));
+    /// ```
+    pub fn as_minutes(&self) -> (u16, u16) {
+        let minutes =
+            |(hour, minute): (u8, u8)| hour as u16 * 60 + minute as u16;
+        (minutes(self.start), minutes(self.end))
+    }
+}
+
+/// Query parameters that don't change the page as given by the `Clean-param`
+/// directive (i.e. `Clean-param: ref&sid /forum`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanParam {
+    /// The names of the query parameters to ignore.
+    pub params: Vec<String>,
+    /// The parameters only apply to paths with this prefix if given.
+    pub path_prefix: Option<String>,
+}
+
+/// A problem found in `robots.txt` that didn't prevent parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// The line number (starting from 1) the warning refers to.
+    pub line: usize,
+    /// The text of the line with surrounding whitespace removed.
+    pub raw: String,
+    pub kind: WarningKind,
+}
+
+/// The kinds of problems reported by [Robot::warnings].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// The file appears to be an HTML page rather than `robots.txt`.
+    ///
+    /// Misconfigured servers may return an error page with a 200 status.
+    /// This produces no rules (allowing everything) so a cautious crawler
+    /// may prefer to treat `robots.txt` as unavailable.
+    LooksLikeHtml,
+    /// A forgiven variation of a directive was used (i.e. `Dissallow`).
+    MisspelledDirective {
+        /// The directive as written in `robots.txt`.
+        used: String,
+        /// The directive it was interpreted as (i.e. `Disallow`).
+        interpreted_as: &'static str,
+    },
+    /// The rule was demoted to match only its literal prefix as the limit
+    /// set by [RobotBuilder::max_compile_rules] was reached.
+    RuleDemoted { pattern: String },
+    /// The rule was skipped as it couldn't be compiled for matching.
+    RuleTooComplex { pattern: String },
+    /// The line was ignored as it isn't a known directive.
+    UnknownDirective,
+    /// The line was ignored as the value of the directive was invalid
+    /// (i.e. `Crawl-Delay: soon`).
+    InvalidValue {
+        /// The directive the line was for (i.e. `Crawl-delay`).
+        directive: &'static str,
+    },
+    /// The line was ignored as the directive wasn't followed by a colon.
+    MissingColon {
+        /// The directive the line was for (i.e. `Disallow`).
+        directive: &'static str,
+    },
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningKind::LooksLikeHtml => {
+                write!(f, "content looks like HTML rather than robots.txt")
+            }
+            WarningKind::MisspelledDirective { used, interpreted_as } => {
+                write!(
+                    f,
+                    "misspelled directive '{}', interpreted as {}",
+                    used, interpreted_as
+                )
+            }
+            WarningKind::RuleDemoted { pattern } => {
+                write!(f, "rule '{}' demoted to its literal prefix", pattern)
+            }
+            WarningKind::RuleTooComplex { pattern } => {
+                write!(f, "rule '{}' skipped as too complex", pattern)
+            }
+            WarningKind::UnknownDirective => write!(f, "unknown directive"),
+            WarningKind::InvalidValue { directive } => {
+                write!(f, "invalid value for {}", directive)
+            }
+            WarningKind::MissingColon { directive } => {
+                write!(f, "missing colon after {}", directive)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: RobotRegex,
+    // The pattern before percent encoding if encoding changed it
+    original: Option<String>,
+    allowed: bool,
+    // Rules with a higher priority win regardless of pattern length
+    // All rules from a single robots.txt share the same priority
+    priority: usize,
+    // The line number (starting from 1) in robots.txt or 0 if not parsed from one
+    line: usize,
+}
+
+impl Rule {
+    // Sort by priority, then according to the longest match, and then by whether it's allowed
+    // RobotRegex is sorted with preference going from longest to shortest
+    // If there are two rules of equal length, allow and disallow, spec says allow
+    fn precedence(&self) -> (Reverse<usize>, &RobotRegex, bool) {
+        (Reverse(self.priority), &self.regex, !self.allowed)
+    }
+}
+
+// The index of each rule in the order they take precedence when matching
+// Ties are broken as given by the Tiebreak (by default the first in robots.txt)
+fn precedence_order(rules: &[Rule], tiebreak: Tiebreak) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..rules.len()).collect();
+    order.sort_by_key(|&idx| {
+        (rules[idx].precedence(), tiebreak.key(rules, idx))
+    });
+    order
+}
+
+// The rules grouped by the first path segment they require (i.e. "/profiles/")
+// so matching a URL only checks the rules that could apply to it
+// Rules are given by their rank (position in Robot::precedence) in ascending order
+#[derive(Debug, Clone, Default)]
+struct RuleIndex {
+    by_segment: HashMap<String, Vec<usize>>,
+    // Rules without a fixed first segment (i.e. "/*.php" or "/fish") may match any URL
+    unindexed: Vec<usize>,
+    // The first Allow rule for an index page by its directory (i.e. "/dir/"
+    // for "/dir/index.html") given by its position in Robot::rules
+    // See RobotBuilder::treat_index_as_directory
+    index_pages: HashMap<String, usize>,
+}
+
+impl RuleIndex {
+    fn new(rules: &[Rule], precedence: &[usize]) -> Self {
+        let mut index = RuleIndex::default();
+        for (rank, &idx) in precedence.iter().enumerate() {
+            match rules[idx].regex.first_segment() {
+                Some(segment) => index
+                    .by_segment
+                    .entry(segment.to_string())
+                    .or_default()
+                    .push(rank),
+                None => index.unindexed.push(rank),
+            }
+        }
+        for (idx, rule) in rules.iter().enumerate().filter(|(_, x)| x.allowed)
+        {
+            if let Some(directory) = index_directory(rule.regex.pattern()) {
+                index.index_pages.entry(directory.to_string()).or_insert(idx);
+            }
+        }
+        index
+    }
+
+    // The ranks of the rules that may match the prepared URL as two ascending lists
+    fn candidates(&self, url: &str) -> [&[usize]; 2] {
+        let indexed = url
+            .strip_prefix('/')
+            .and_then(|rest| rest.split_once('/'))
+            .and_then(|(segment, _)| self.by_segment.get(segment));
+        [indexed.map_or(&[], |ranks| ranks.as_slice()), &self.unindexed]
+    }
+}
+
+// The regex backed rules of one type (Allow or Disallow) in a single set
+#[derive(Debug, Clone)]
+struct CombinedRules {
+    set: RegexSet,
+    // The index in Robot::rules of each regex in the set
+    rules: Vec<usize>,
+}
+
+// Combine the regex backed Allow and Disallow rules into a set for each
+// If a set can't be built (i.e. exceeding size limits) we use individual rules
+fn combine_rules(rules: &[Rule], size_limit: usize) -> Vec<CombinedRules> {
+    let mut combined = vec![];
+    // Overlaid rules may differ in case sensitivity so each is kept separate
+    for (allowed, insensitive) in
+        [(true, false), (true, true), (false, false), (false, true)]
+    {
+        let (patterns, indices): (Vec<&str>, Vec<usize>) = rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| {
+                rule.allowed == allowed
+                    && rule.regex.is_case_insensitive() == insensitive
+            })
+            .filter_map(|(idx, rule)| Some((rule.regex.regex_str()?, idx)))
+            .unzip();
+        if patterns.is_empty() {
+            continue;
+        }
+        // Each regex has already been built within the per rule limits
+        let limit = patterns.len().saturating_mul(size_limit);
+        let set = RegexSetBuilder::new(patterns)
+            .size_limit(limit)
+            .dfa_size_limit(limit)
+            .case_insensitive(insensitive)
+            .build();
+        match set {
+            Ok(set) => combined.push(CombinedRules { set, rules: indices }),
+            Err(_) => return vec![],
+        }
+    }
+    combined
+}
+
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct Robot {
-    // Rules are stored in the form of (regex rule, allow/disallow)
-    // where the regex rule is ordered by original pattern length
-    rules: Vec<(RobotRegex, bool)>,
+    // Rules are stored with the regex rule ordered by original pattern length
+    rules: Vec<Rule>,
     /// The delay in seconds between requests.
     /// If `Crawl-Delay` is set in `robots.txt` it will return `Some(f32)`
     /// and otherwise `None`.
     pub delay: Option<f32>,
+    /// The delay in seconds between requests given by `Request-rate` (i.e.
+    /// `Request-rate: 1/10s` is one request every ten seconds).
+    /// This is kept separate from `delay` so either may be honored.
+    pub request_rate: Option<f32>,
     /// Any sitemaps found in the `robots.txt` file are added to this vector.
     /// According to the `robots.txt` specification a sitemap found in `robots.txt`
     /// is accessible and available to any bot reading `robots.txt`.
     pub sitemaps: Vec<String>,
+    /// The preferred mirror given by the last valid `Host` directive in
+    /// `robots.txt` (i.e. `example.com` or `https://example.com`).
+    /// Like sitemaps this isn't tied to any user agent.
+    pub host: Option<String>,
+    // Number of bytes the parser left unconsumed (expected to be zero)
+    unparsed: usize,
+    visit_time: Option<VisitTime>,
+    clean_params: Vec<CleanParam>,
+    noindex: Vec<String>,
+    warnings: Vec<ParseWarning>,
+    // Indices into rules sorted by precedence so matching needs no per URL sort
+    precedence: Vec<usize>,
+    index: RuleIndex,
+    tiebreak: Tiebreak,
+    access: Access,
+    // Regex rules combined into sets if requested (see RobotBuilder::combine_regex_rules)
+    combined: Vec<CombinedRules>,
+    // The size limit each regex was compiled within (see RobotBuilder::regex_size_limit)
+    regex_size_limit: usize,
+    // The User-Agent token as written in robots.txt for the group that matched
+    matched_agent: Option<String>,
+    // Decode unreserved characters in URLs before matching (see RobotBuilder)
+    normalize_percent_encoding: bool,
+    // Check an index page by its directory if no rule matched (see RobotBuilder)
+    index_as_directory: bool,
 }
 
 impl fmt::Debug for Robot {
@@ -331,196 +1233,1780 @@ impl fmt::Debug for Robot {
         f.debug_struct("Robot")
             .field("rules", &self.rules)
             .field("delay", &self.delay)
+            .field("request_rate", &self.request_rate)
             .field("sitemaps", &self.sitemaps)
+            .field("host", &self.host)
             .finish()
     }
 }
 
-impl Robot {
-    /// Construct a new Robot object specifically processed for the given user agent.
-    /// The user agent extracts all relevant rules from `robots.txt` and stores them
-    /// internally. If the user agent isn't found in `robots.txt` we default to `*`.
-    ///
-    /// Note: The agent string is lowercased before comparison, as required by the
-    /// `robots.txt` specification.
-    ///
-    /// # Errors
-    ///
-    /// If there are difficulties parsing, which should be rare as the parser is quite
-    /// forgiving, then an [InvalidRobots](Error::InvalidRobots) error is returned.
-    pub fn new(agent: &str, txt: &[u8]) -> Result<Self, anyhow::Error> {
-        // Replace '\x00' with '\n'
-        // This shouldn't be necessary but some websites are strange ...
-        let txt = txt
-            .iter()
-            .map(|x| if *x == 0 { b'\n' } else { *x })
-            .collect::<Vec<u8>>();
+/// The outcome of [Robot::gate] for a URL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gate {
+    /// The URL may be fetched, waiting `delay` before the next request.
+    Allowed { delay: Option<Duration> },
+    /// The URL may not be fetched as the given Disallow pattern matched.
+    Blocked { rule: String },
+}
 
-        // Parse robots.txt using the nom library
-        let lines = match robots_txt_parse(&txt) {
-            Ok((_, lines)) => lines,
-            Err(e) => {
-                let err = anyhow::Error::new(Error::InvalidRobots)
-                    .context(e.to_string());
-                return Err(err);
-            }
-        };
+/// How much of a site an agent may crawl as given by [Robot::access].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Every URL is allowed so no per URL checks are needed.
+    Allowed,
+    /// Every URL (other than `/robots.txt`) is disallowed.
+    Disallowed,
+    /// Some URLs are allowed and each must be checked with [Robot::allowed].
+    Restricted,
+}
+
+/// How to choose between matching rules of equal pattern length and the same
+/// type (Allow or Disallow) as set by [Robot::with_longest_match_tiebreak].
+///
+/// Whether a URL is allowed never depends on the tiebreak but the rule given
+/// by [Robot::allowed_explain] and [Robot::gate] does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tiebreak {
+    /// The rule appearing first in `robots.txt` wins.
+    #[default]
+    FirstWins,
+    /// The rule appearing last in `robots.txt` wins.
+    LastWins,
+    /// The rule with the most path segments (`/`) wins with any remaining
+    /// ties going to the first in `robots.txt`.
+    MostSpecificSegments,
+}
+
+impl Tiebreak {
+    // Sort key for the rule at idx with lower keys winning
+    fn key(&self, rules: &[Rule], idx: usize) -> (Reverse<usize>, usize) {
+        match self {
+            Tiebreak::FirstWins => (Reverse(0), idx),
+            Tiebreak::LastWins => (Reverse(0), usize::MAX - idx),
+            Tiebreak::MostSpecificSegments => {
+                let pattern = rules[idx].regex.pattern();
+                (Reverse(pattern.matches('/').count()), idx)
+            }
+        }
+    }
+}
+
+// Classify the access given by the rules, computed once at construction
+fn classify_access(rules: &[Rule]) -> Access {
+    let universal_allows: Vec<&Rule> = rules
+        .iter()
+        .filter(|rule| rule.allowed && rule.regex.matches_everything())
+        .collect();
+    let allows_everything =
+        rules.iter().filter(|rule| !rule.allowed).all(|disallow| {
+            // The universal allow must win against the disallow on every path
+            universal_allows.iter().any(|allow| {
+                (Reverse(allow.priority), &allow.regex)
+                    <= (Reverse(disallow.priority), &disallow.regex)
+            })
+        });
+    // Any Allow rule could override a universal Disallow for some paths
+    let disallows_everything = !rules.iter().any(|rule| rule.allowed)
+        && rules.iter().any(|rule| rule.regex.matches_everything());
+    match (allows_everything, disallows_everything) {
+        (true, _) => Access::Allowed,
+        (false, true) => Access::Disallowed,
+        (false, false) => Access::Restricted,
+    }
+}
+
+/// The directive of a rule in `robots.txt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    Allow,
+    Disallow,
+}
+
+/// How a rule in `robots.txt` is matched against a URL.
+///
+/// Redundant wildcards are removed first so `/fish*` is a literal rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleKind {
+    /// The URL starts with the pattern (i.e. `/fish`).
+    Literal,
+    /// The pattern contains `*` (i.e. `/fish*.php`).
+    Wildcard,
+    /// The pattern ends in `$` and must match the entire URL (i.e. `/*.php$`).
+    Anchored,
+}
+
+/// The outcome of [Robot::allowed_explain] for a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decision<'a> {
+    /// Whether the URL is allowed as given by [Robot::allowed].
+    pub allowed: bool,
+    /// The pattern of the deciding rule as given by [Robot::rules] or `None`
+    /// if no rule decided the outcome.
+    pub pattern: Option<&'a str>,
+    /// Whether the deciding rule was an Allow or a Disallow or `None` if no
+    /// rule decided the outcome.
+    pub directive: Option<Directive>,
+    /// The number of bytes of the path and query matched by the deciding rule
+    /// or `None` if no rule decided the outcome. Rules ending in `$` match
+    /// the entire path and query.
+    pub matched_len: Option<usize>,
+    /// How the deciding rule is matched or `None` if no rule decided the
+    /// outcome.
+    pub kind: Option<RuleKind>,
+}
+
+// Whether the directive is spelled as in RFC 9309 and followed by a colon
+fn is_strict_directive(line: &[u8]) -> bool {
+    let is_space = |c: char| c == ' ' || c == '\t';
+    match directive_keyword(line) {
+        Some((used, directive))
+            if used.eq_ignore_ascii_case(directive.as_bytes()) =>
+        {
+            let line = line.trim_start_with(is_space);
+            line[used.len()..].trim_start_with(is_space).starts_with(b":")
+        }
+        _ => false,
+    }
+}
+
+// Why a line that wasn't interpreted was ignored, if it wasn't blank or a comment
+fn ignored_line_reason(line: &[u8], strict: bool) -> Option<WarningKind> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with(b"#") {
+        return None;
+    }
+    let (used, directive) = match directive_keyword(line) {
+        Some(keyword) => keyword,
+        None => return Some(WarningKind::UnknownDirective),
+    };
+    let is_space = |c: char| c == ' ' || c == '\t';
+    let rest = &line[used.len()..];
+    let value = rest.trim_start_with(is_space);
+    let spaced = value.len() < rest.len();
+    let misspelled = !used.eq_ignore_ascii_case(directive.as_bytes());
+    let reason = match value.first() {
+        _ if strict && misspelled => WarningKind::UnknownDirective,
+        // The keyword is only the start of a longer word (i.e. "Allowance")
+        Some(c) if !spaced && (c.is_ascii_alphanumeric() || *c == b'-') => {
+            WarningKind::UnknownDirective
+        }
+        Some(b':') => WarningKind::InvalidValue { directive },
+        Some(_) if spaced && !strict => {
+            WarningKind::InvalidValue { directive }
+        }
+        _ => WarningKind::MissingColon { directive },
+    };
+    Some(reason)
+}
+
+// Google's recommended limit on the size of robots.txt (500 kibibytes)
+const DEFAULT_MAX_BYTES: usize = 500 * 1024;
+
+// Options set through the RobotBuilder that change how robots.txt is interpreted
+#[derive(Debug, Clone)]
+struct Options {
+    strip_agent_version: bool,
+    match_agent_product_token: bool,
+    combine_regex_rules: bool,
+    max_compile_rules: Option<usize>,
+    max_bytes: usize,
+    regex_size_limit: usize,
+    match_scan_limit: usize,
+    respect_request_rate: bool,
+    case_insensitive: bool,
+    normalize_percent_encoding: bool,
+    strict_crawl_delay: bool,
+    strict: bool,
+    literal_hash_in_rules: bool,
+    index_as_directory: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            strip_agent_version: false,
+            match_agent_product_token: false,
+            combine_regex_rules: false,
+            max_compile_rules: None,
+            max_bytes: DEFAULT_MAX_BYTES,
+            regex_size_limit: DEFAULT_REGEX_SIZE_LIMIT,
+            match_scan_limit: DEFAULT_MATCH_SCAN_LIMIT,
+            respect_request_rate: false,
+            case_insensitive: false,
+            normalize_percent_encoding: false,
+            strict_crawl_delay: false,
+            strict: false,
+            literal_hash_in_rules: false,
+            index_as_directory: false,
+        }
+    }
+}
+
+/// A builder for a [Robot] with options beyond the defaults of [Robot::new].
+///
+/// # Example
+///
+/// ```rust
+/// use texting_robots::RobotBuilder;
+///
+/// let txt = "User-Agent: FerrisCrawler\nDisallow: /rust";
+/// let r = RobotBuilder::new("FerrisCrawler/1.2")
+///     .strip_agent_version(true)
+///     .build(txt.as_bytes())
+///     .unwrap();
+/// assert_eq!(r.allowed("/rust"), false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RobotBuilder {
+    agent: String,
+    options: Options,
+}
+
+impl RobotBuilder {
+    /// Start building a Robot for the given user agent.
+    pub fn new(agent: &str) -> Self {
+        RobotBuilder { agent: agent.to_string(), options: Options::default() }
+    }
+
+    /// Ignore any version in the agent (i.e. `FerrisCrawler/1.2` is
+    /// treated as `FerrisCrawler`) when finding the agent in `robots.txt`.
+    ///
+    /// Defaults to false.
+    pub fn strip_agent_version(mut self, strip: bool) -> Self {
+        self.options.strip_agent_version = strip;
+        self
+    }
+
+    /// Use only the product token of the agent, being the text up to the
+    /// first `/` or space, when finding the agent in `robots.txt` as Google does.
+    ///
+    /// This allows passing a crawler's full User-Agent string (i.e.
+    /// `Googlebot/2.1 (+http://www.google.com/bot.html)` is treated as
+    /// `Googlebot`). Note the first token is used so an agent such as
+    /// `Mozilla/5.0 (compatible; Googlebot/2.1)` is treated as `Mozilla`.
+    ///
+    /// Defaults to false.
+    pub fn match_agent_product_token(mut self, product_token: bool) -> Self {
+        self.options.match_agent_product_token = product_token;
+        self
+    }
+
+    /// Combine the rules requiring a regex into one set for Allow rules and
+    /// one set for Disallow rules, each checked with a single scan.
+    ///
+    /// This speeds up matching for files with many wildcard rules (i.e. using
+    /// `*` and `$`) at the cost of construction time. Results are identical.
+    ///
+    /// Defaults to false.
+    pub fn combine_regex_rules(mut self, combine: bool) -> Self {
+        self.options.combine_regex_rules = combine;
+        self
+    }
+
+    /// Stop compiling regexes for rules after `max` rules have required one.
+    ///
+    /// This bounds the worst case construction time for adversarial files with
+    /// many complex rules. Any further rules requiring a regex are demoted to
+    /// match only their literal prefix (i.e. `/a/*/b$` becomes `/a/`) with a
+    /// [RuleDemoted](WarningKind::RuleDemoted) warning.
+    ///
+    /// Defaults to no limit.
+    pub fn max_compile_rules(mut self, max: usize) -> Self {
+        self.options.max_compile_rules = Some(max);
+        self
+    }
+
+    /// Ignore any input past `max` bytes, keeping only the complete lines
+    /// before the limit.
+    ///
+    /// Defaults to Google's recommended limit of 500 kibibytes.
+    pub fn max_bytes(mut self, max: usize) -> Self {
+        self.options.max_bytes = max;
+        self
+    }
+
+    /// Limit the memory used by the regex compiled for each complex rule
+    /// (i.e. `/a/*/b/*/c$`) to `limit` bytes.
+    ///
+    /// A smaller limit suits constrained deployments while a larger one
+    /// avoids falling back for complex rules. Rules whose regex would exceed
+    /// the limit are still matched exactly though without a regex.
+    ///
+    /// Defaults to 42 kibibytes.
+    pub fn regex_size_limit(mut self, limit: usize) -> Self {
+        self.options.regex_size_limit = limit;
+        self
+    }
+
+    /// Limit the work of matching a wildcard rule without a regex (i.e.
+    /// `/a*b*c`) against a URL to scanning `limit` bytes of the URL.
+    ///
+    /// Matching is linear in the length of the URL and pattern so this only
+    /// bounds adversarial inputs. Exceeding the limit fails closed: a
+    /// Disallow rule is taken as matching the whole URL while an Allow rule
+    /// is taken as not matching.
+    ///
+    /// Defaults to 1 mebibyte.
+    pub fn match_scan_limit(mut self, limit: usize) -> Self {
+        self.options.match_scan_limit = limit;
+        self
+    }
+
+    /// Honor `Request-rate` in the crawl delay by setting `delay` to the
+    /// slower of `Crawl-Delay` and `Request-rate` (i.e. `Request-rate: 1/10s`
+    /// with no `Crawl-Delay` gives a delay of ten seconds).
+    ///
+    /// The `request_rate` field is populated either way.
+    ///
+    /// Defaults to false.
+    pub fn respect_request_rate(mut self, respect: bool) -> Self {
+        self.options.respect_request_rate = respect;
+        self
+    }
+
+    /// Match paths ignoring ASCII case (i.e. `Disallow: /Admin` also blocks
+    /// `/admin`) for sites served from case insensitive file systems.
+    ///
+    /// The `robots.txt` specification says paths are case sensitive so this
+    /// should only be used when a site is known to ignore case.
+    ///
+    /// Defaults to false.
+    pub fn case_insensitive(mut self, insensitive: bool) -> Self {
+        self.options.case_insensitive = insensitive;
+        self
+    }
+
+    /// Decode percent encoded unreserved characters (i.e. `%41` becomes `A`)
+    /// as defined by RFC 3986 in both rules and URLs before matching.
+    ///
+    /// This means `Disallow: /foo/bar/%62%61%7A` blocks `/foo/bar/baz`.
+    /// Reserved characters such as `%2F` (`/`) remain encoded as decoding
+    /// them would change the meaning of the URL.
+    ///
+    /// Defaults to false.
+    pub fn normalize_percent_encoding(mut self, normalize: bool) -> Self {
+        self.options.normalize_percent_encoding = normalize;
+        self
+    }
+
+    /// Only use a `Crawl-Delay` that belongs to the matched group, being
+    /// before the next User-Agent line in `robots.txt`.
+    ///
+    /// By default a line that fails to parse (i.e. `Crawl-Delay: -1.25`) is
+    /// dropped which can join the group with the next and leak its delay.
+    /// With this set such a line instead ends the group so the delay is `None`.
+    /// A `Crawl-Delay` before any User-Agent line is also no longer used when
+    /// a group matched.
+    ///
+    /// Defaults to false.
+    pub fn strict_crawl_delay(mut self, strict: bool) -> Self {
+        self.options.strict_crawl_delay = strict;
+        self
+    }
+
+    /// Follow RFC 9309 strictly rather than forgiving common mistakes.
+    ///
+    /// Directives must be spelled as in the specification and followed by a
+    /// colon, so `Dissallow: /a` or `Disallow /a` are ignored. As the RFC sets
+    /// no limit on the length of a line, only the input limit applies (see
+    /// [max_bytes](RobotBuilder::max_bytes)) with a line cut by it ignored.
+    ///
+    /// Defaults to false.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.options.strict = strict;
+        self
+    }
+
+    /// Keep a `#` within an Allow or Disallow rule rather than treating it
+    /// as the start of a comment, as some parsers do.
+    ///
+    /// A `#` then only starts a comment at the start of the value or when
+    /// following whitespace, so `Disallow: /a#b # note` is the rule `/a#b`.
+    /// This suits files where `#` is part of the path being matched though a
+    /// comment written without whitespace (i.e. `Disallow: /a#note`) becomes
+    /// part of the rule, making it more specific than intended. Google and
+    /// RFC 9309 treat any `#` as the start of a comment.
+    ///
+    /// Defaults to false.
+    pub fn literal_hash_in_rules(mut self, literal: bool) -> Self {
+        self.options.literal_hash_in_rules = literal;
+        self
+    }
+
+    /// Treat an index page (`index.html` or `index.htm`) as the same page as
+    /// its directory, as Googlebot does.
+    ///
+    /// An Allow for `/dir/index.html` then also allows `/dir/` exactly (as if
+    /// it were the rule `/dir/$`). When checking `/dir/index.html` and no rule
+    /// matches, the rules for `/dir/` decide instead. This only changes how
+    /// URLs are checked and no rules are added to [Robot::rules].
+    ///
+    /// Defaults to false as RFC 9309 treats them as separate paths.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::RobotBuilder;
+    ///
+    /// let txt = b"Allow: /ocean/index.html\nDisallow: /";
+    /// let r = RobotBuilder::new("Ferris")
+    ///     .treat_index_as_directory(true)
+    ///     .build(txt)
+    ///     .unwrap();
+    /// assert_eq!(r.allowed("/ocean/"), true);
+    /// assert_eq!(r.allowed("/ocean/fish"), false);
+    /// ```
+    pub fn treat_index_as_directory(mut self, treat: bool) -> Self {
+        self.options.index_as_directory = treat;
+        self
+    }
+
+    /// Construct the Robot from `robots.txt`.
+    ///
+    /// # Errors
+    ///
+    /// See [Robot::new].
+    pub fn build(&self, txt: &[u8]) -> Result<Robot, anyhow::Error> {
+        Robot::with_options(&self.agent, txt, &self.options)
+    }
+}
+
+impl Robot {
+    /// Construct a new Robot object specifically processed for the given user agent.
+    /// The user agent extracts all relevant rules from `robots.txt` and stores them
+    /// internally. If the user agent isn't found in `robots.txt` we default to `*`.
+    ///
+    /// Note: The agent string is lowercased before comparison, as required by the
+    /// `robots.txt` specification.
+    ///
+    /// The most specific group is used, being the longest User-Agent in `robots.txt`
+    /// that is a prefix of the agent (i.e. `Googlebot-News` uses the `Googlebot`
+    /// group if there's no `Googlebot-News` group). The prefix must be followed
+    /// by `-`, `_`, `/`, or whitespace so `a` doesn't apply to `applebot`.
+    ///
+    /// For more control over how `robots.txt` is interpreted see [RobotBuilder].
+    ///
+    /// # Errors
+    ///
+    /// If there are difficulties parsing, which should be rare as the parser is quite
+    /// forgiving, then an [Parse](Error::Parse) error is returned.
+    pub fn new(agent: &str, txt: &[u8]) -> Result<Self, anyhow::Error> {
+        RobotBuilder::new(agent).build(txt)
+    }
+
+    /// Construct a new Robot as in [Robot::new] along with the problems found
+    /// in `robots.txt`, including each line that was ignored and why.
+    ///
+    /// The warnings are the same as those given by [Robot::warnings].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::{Robot, WarningKind};
+    ///
+    /// let txt = b"Disallow: /rust\nCrawl-Delay: soon\nNoarchive: /ocean";
+    /// let (r, warnings) = Robot::new_with_report("Ferris", txt).unwrap();
+    /// assert_eq!(r.allowed("/rust"), false);
+    /// assert_eq!(warnings[0].line, 2);
+    /// assert_eq!(warnings[0].raw, "Crawl-Delay: soon");
+    /// assert_eq!(warnings[0].kind, WarningKind::InvalidValue { directive: "Crawl-delay" });
+    /// assert_eq!(warnings[1].kind, WarningKind::UnknownDirective);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [Robot::new].
+    pub fn new_with_report(
+        agent: &str,
+        txt: &[u8],
+    ) -> Result<(Self, Vec<ParseWarning>), anyhow::Error> {
+        let robot = Robot::new(agent, txt)?;
+        let warnings = robot.warnings.clone();
+        Ok((robot, warnings))
+    }
+
+    /// Construct a new Robot as in [Robot::new] following RFC 9309 strictly.
+    ///
+    /// This is intended for checking that `robots.txt` is conformant rather
+    /// than crawling. See [RobotBuilder::strict] for the differences.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"Disallow: /rust\nDissallow: /ocean\nDisallow /forest";
+    /// let r = Robot::new_strict("Ferris", txt).unwrap();
+    /// assert_eq!(r.allowed("/rust"), false);
+    /// assert_eq!(r.allowed("/ocean"), true);
+    /// assert_eq!(r.allowed("/forest"), true);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [Robot::new].
+    pub fn new_strict(agent: &str, txt: &[u8]) -> Result<Self, anyhow::Error> {
+        RobotBuilder::new(agent).strict(true).build(txt)
+    }
+
+    /// Construct a new Robot as in [Robot::new] ignoring any input past
+    /// `max_bytes` rather than Google's recommended 500 kibibytes.
+    ///
+    /// As with Google, input past the limit is ignored rather than an error.
+    /// Only the complete lines before the limit are kept so a rule is never
+    /// cut short.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"Disallow: /rust\nDisallow: /ocean";
+    /// let r = Robot::new_with_limit("Ferris", txt, 20).unwrap();
+    /// assert_eq!(r.allowed("/rust"), false);
+    /// assert_eq!(r.allowed("/ocean"), true);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [Robot::new].
+    pub fn new_with_limit(
+        agent: &str,
+        txt: &[u8],
+        max_bytes: usize,
+    ) -> Result<Self, anyhow::Error> {
+        RobotBuilder::new(agent).max_bytes(max_bytes).build(txt)
+    }
+
+    fn with_options(
+        agent: &str,
+        txt: &[u8],
+        options: &Options,
+    ) -> Result<Self, anyhow::Error> {
+        let original = truncate_input(txt, options.max_bytes);
+        let txt = replace_nulls(original);
+
+        // Parse robots.txt using the nom library
+        let (unparsed, raw_lines) = match robots_txt_parse_with_raw(&txt) {
+            Ok((remaining, lines)) => (remaining.len(), lines),
+            Err(e) => {
+                let err =
+                    anyhow::Error::new(Error::Parse).context(e.to_string());
+                return Err(err);
+            }
+        };
+        let numbers = editor_line_numbers(original, &raw_lines, unparsed);
+        Self::from_raw_lines(agent, raw_lines, &numbers, unparsed, options)
+    }
+
+    // Construct the Robot from each parsed line, the raw text it was parsed
+    // from (with nulls replaced), and the line number editors show for it
+    fn from_raw_lines<'a>(
+        agent: &str,
+        mut raw_lines: Vec<(&'a [u8], Line<'a>)>,
+        numbers: &[usize],
+        unparsed: usize,
+        options: &Options,
+    ) -> Result<Self, anyhow::Error> {
+        // Without forgiveness any directive not written as in RFC 9309 is ignored
+        if options.strict {
+            for (raw, line) in raw_lines.iter_mut() {
+                if !matches!(line, Line::Raw(_)) && !is_strict_directive(raw) {
+                    *line = Line::Raw(raw);
+                }
+            }
+        }
+
+        if options.literal_hash_in_rules {
+            for (raw, line) in raw_lines.iter_mut() {
+                if matches!(line, Line::Allow(_) | Line::Disallow(_)) {
+                    if let Some(rule) = rule_with_literal_hash(raw) {
+                        *line = rule;
+                    }
+                }
+            }
+        }
+
+        let lines: Vec<Line> = raw_lines.iter().map(|(_, x)| *x).collect();
+
+        let mut warnings = vec![];
+
+        // Misconfigured servers may return an HTML page instead of robots.txt
+        let first_content = lines.iter().enumerate().find(|(_, x)| match x {
+            Line::Raw(r) => !r.trim().is_empty(),
+            _ => true,
+        });
+        let looks_like_html = match first_content {
+            Some((idx, Line::Raw(r))) if r.trim_start().starts_with(b"<") => {
+                warnings.push(ParseWarning {
+                    line: numbers[idx],
+                    raw: r.trim().to_str_lossy().into_owned(),
+                    kind: WarningKind::LooksLikeHtml,
+                });
+                true
+            }
+            _ => false,
+        };
+
+        // Report any forgiven variations of the directives (i.e. "dissallow")
+        // and any lines ignored as they couldn't be interpreted
+        for (idx, (raw, line)) in raw_lines.iter().enumerate() {
+            let kind = match line {
+                // Reporting each line of an HTML page would only be noise
+                Line::Raw(_) if looks_like_html => None,
+                Line::Raw(_) => ignored_line_reason(raw, options.strict),
+                _ => directive_keyword(raw)
+                    .filter(|(used, directive)| {
+                        !used.eq_ignore_ascii_case(directive.as_bytes())
+                    })
+                    .map(|(used, directive)| {
+                        WarningKind::MisspelledDirective {
+                            used: used.to_str_lossy().into_owned(),
+                            interpreted_as: directive,
+                        }
+                    }),
+            };
+            if let Some(kind) = kind {
+                warnings.push(ParseWarning {
+                    line: numbers[idx],
+                    raw: raw.trim().to_str_lossy().into_owned(),
+                    kind,
+                });
+            }
+        }
+
+        // Crawlers often identify with a version (i.e. "FerrisCrawler/1.2")
+        let agent = match options.strip_agent_version {
+            true => agent.split('/').next().unwrap_or(agent).trim(),
+            false => agent,
+        };
+        let agent = match options.match_agent_product_token {
+            true => agent
+                .split(|c: char| c == '/' || c.is_whitespace())
+                .find(|token| !token.is_empty())
+                .unwrap_or(agent),
+            false => agent,
+        };
 
         // All agents are case insensitive in `robots.txt`
         let agent = agent.to_lowercase();
-        let mut agent = agent.as_str();
 
         // Collect all sitemaps
         // Why? "The sitemap field isn't tied to any specific user agent and may be followed by all crawlers"
         let sitemaps = lines
             .iter()
             .filter_map(|x| match x {
-                Line::Sitemap(url) => match String::from_utf8(url.to_vec()) {
-                    Ok(url) => Some(url),
-                    Err(_) => None,
-                },
+                Line::Sitemap(url) => String::from_utf8(url.to_vec()).ok(),
+                _ => None,
+            })
+            .collect();
+
+        // Keep the last valid Host which, as with sitemaps, applies to all crawlers
+        let host = lines
+            .iter()
+            .rev()
+            .filter_map(|x| match x {
+                Line::Host(host) => host.to_str().ok(),
+                _ => None,
+            })
+            .find(|host| is_valid_host(host))
+            .map(|host| host.to_string());
+
+        // Collect all Clean-param directives which also apply to all crawlers
+        let clean_params = lines
+            .iter()
+            .filter_map(|x| match x {
+                Line::CleanParam { params, path } => Some(CleanParam {
+                    params: params
+                        .split_str("&")
+                        .map(|p| p.to_str_lossy().into_owned())
+                        .collect(),
+                    path_prefix: path.map(|p| p.to_str_lossy().into_owned()),
+                }),
                 _ => None,
             })
             .collect();
 
         // Filter out any lines that aren't User-Agent, Allow, Disallow, or CrawlDelay
         // CONFLICT: reppy's "test_robot_grouping_unknown_keys" test suggests these lines should be kept
-        let lines: Vec<Line> = lines
+        let lines: Vec<(usize, Line)> = lines
             .iter()
-            .filter(|x| !matches!(x, Line::Sitemap(_) | Line::Raw(_)))
-            .copied()
+            .enumerate()
+            .map(|(idx, x)| (idx + 1, *x))
+            .filter(|(_, x)| {
+                !matches!(
+                    x,
+                    Line::Sitemap(_)
+                        | Line::Host(_)
+                        | Line::CleanParam { .. }
+                        | Line::Raw(_)
+                )
+            })
             .collect();
 
-        // Check if our crawler is explicitly referenced, otherwise we're catch all agent ("*")
-        let references_our_bot = lines.iter().any(|x| match x {
-            Line::UserAgent(ua) => {
-                agent.as_bytes() == ua.as_bstr().to_ascii_lowercase()
+        let (subset, matched_agent) = group_for_agent(&agent, &lines);
+        let matched_agent =
+            matched_agent.map(|ua| ua.to_str_lossy().into_owned());
+        let delay = match (options.strict_crawl_delay, &matched_agent) {
+            (true, Some(_)) => {
+                // Keep any line with content that failed to parse as it ends the group
+                let lines: Vec<(usize, Line)> = raw_lines
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, (_, x))| (idx + 1, *x))
+                    .filter(|(_, x)| match x {
+                        Line::Raw(r) => {
+                            let r = r.trim();
+                            !r.is_empty() && !r.starts_with(b"#")
+                        }
+                        x => {
+                            !matches!(
+                                x,
+                                Line::Sitemap(_)
+                                    | Line::Host(_)
+                                    | Line::CleanParam { .. }
+                            ) && !within_agent_block(x)
+                        }
+                    })
+                    .collect();
+                let (subset, _) = group_for_agent(&agent, &lines);
+                subset.iter().find_map(|(_, x)| match x {
+                    Line::CrawlDelay(delay) => *delay,
+                    _ => None,
+                })
             }
-            _ => false,
-        });
-        if !references_our_bot {
-            agent = "*";
-        }
+            _ => crawl_delay(&subset, &lines),
+        };
 
-        // Collect only the lines relevant to this user agent
-        // If there are no User-Agent lines then we capture all
-        let mut capturing = false;
-        if lines.iter().filter(|x| matches!(x, Line::UserAgent(_))).count()
-            == 0
-        {
-            capturing = true;
-        }
-        let mut subset = vec![];
-        let mut idx: usize = 0;
-        while idx < lines.len() {
-            let mut line = lines[idx];
+        // Collect the request rate as seconds per request
+        let request_rate = subset.iter().find_map(|(_, x)| match x {
+            Line::RequestRate(rate) => Some(*rate),
+            _ => None,
+        });
 
-            // User-Agents can be given in blocks with rules applicable to all User-Agents in the block
-            // On a new block of User-Agents we're either in it or no longer active
-            if let Line::UserAgent(_) = line {
-                capturing = false;
-            }
-            while idx < lines.len() && matches!(line, Line::UserAgent(_)) {
-                // Unreachable should never trigger as we ensure it's always a UserAgent
-                let ua = match line {
-                    Line::UserAgent(ua) => ua.as_bstr(),
-                    _ => unreachable!(),
-                };
-                if agent.as_bytes() == ua.as_bstr().to_ascii_lowercase() {
-                    capturing = true;
-                }
-                idx += 1;
-                // If it's User-Agent until the end just escape to avoid potential User-Agent capture
-                if idx == lines.len() {
-                    break;
-                }
-                line = lines[idx];
-            }
+        let delay = match (options.respect_request_rate, request_rate) {
+            (true, Some(rate)) => Some(delay.map_or(rate, |d| d.max(rate))),
+            _ => delay,
+        };
 
-            if capturing {
-                subset.push(line);
+        // Collect the preferred visit time
+        let visit_time = subset.iter().find_map(|(_, x)| match x {
+            Line::VisitTime { start, end } => {
+                Some(VisitTime { start: *start, end: *end })
             }
-            idx += 1;
-        }
+            _ => None,
+        });
 
-        // Collect the crawl delay
-        let mut delay = subset
+        // Noindex hints are kept separately as they don't affect crawling
+        let noindex = subset
             .iter()
-            .filter_map(|x| match x {
-                Line::CrawlDelay(Some(d)) => Some(d),
+            .filter_map(|(_, x)| match x {
+                Line::Noindex(pat) => trim_rule(pat).map(percent_encode),
                 _ => None,
             })
-            .copied()
-            .next();
-
-        // Special note for crawl delay:
-        // Some robots.txt files have it at the top, before any User-Agent lines, to apply to all
-        if delay.is_none() {
-            for line in lines.iter() {
-                if let Line::CrawlDelay(Some(d)) = line {
-                    delay = Some(*d);
-                }
-                if let Line::UserAgent(_) = line {
-                    break;
-                }
-            }
-        }
+            .collect();
 
         // Prepare the regex patterns for matching rules
         let mut rules = vec![];
-        for line in subset
+        let mut compiled = 0;
+        for (number, line) in subset
             .iter()
-            .filter(|x| matches!(x, Line::Allow(_) | Line::Disallow(_)))
+            .filter(|(_, x)| matches!(x, Line::Allow(_) | Line::Disallow(_)))
         {
             let (is_allowed, original) = match line {
                 Line::Allow(pat) => (true, *pat),
                 Line::Disallow(pat) => (false, *pat),
                 _ => unreachable!(),
             };
-            let pat = match original.to_str() {
-                Ok(pat) => pat,
-                Err(_) => continue,
+            let pat = match trim_rule(original) {
+                Some(pat) => pat,
+                None => continue,
             };
 
             // Paths outside ASCII must be percent encoded
-            let pat = percent_encode(pat);
-
-            let rule = RobotRegex::new(&pat);
+            let encoded = percent_encode(pat);
+            let encoded = match options.normalize_percent_encoding {
+                true => decode_unreserved(&encoded),
+                false => encoded,
+            };
+            // Only keep the original pattern for display if it differs
+            let original = Some(pat.to_string()).filter(|pat| *pat != encoded);
+            let pat = encoded;
+
+            // Bound the construction time by limiting how many regexes are compiled
+            let demote = match options.max_compile_rules {
+                Some(max) => {
+                    compiled >= max && RobotRegex::requires_regex(&pat)
+                }
+                None => false,
+            };
+            let rule = match demote {
+                true => {
+                    warnings.push(ParseWarning {
+                        line: numbers[*number - 1],
+                        raw: raw_lines[*number - 1]
+                            .0
+                            .trim()
+                            .to_str_lossy()
+                            .into_owned(),
+                        kind: WarningKind::RuleDemoted {
+                            pattern: pat.clone(),
+                        },
+                    });
+                    Ok(RobotRegex::literal_prefix(
+                        &pat,
+                        options.case_insensitive,
+                    ))
+                }
+                false => RobotRegex::new_with_limit(
+                    &pat,
+                    options.case_insensitive,
+                    options.regex_size_limit,
+                ),
+            };
 
+            // A single rule that can't be compiled shouldn't reject the file
             let rule = match rule {
                 Ok(rule) => rule,
-                Err(e) => {
-                    let err = anyhow::Error::new(e)
-                        .context(format!("Invalid robots.txt rule: {}", pat));
-                    return Err(err);
+                Err(_) => {
+                    warnings.push(ParseWarning {
+                        line: numbers[*number - 1],
+                        raw: raw_lines[*number - 1]
+                            .0
+                            .trim()
+                            .to_str_lossy()
+                            .into_owned(),
+                        kind: WarningKind::RuleTooComplex { pattern: pat },
+                    });
+                    continue;
                 }
             };
-            rules.push((rule, is_allowed));
-        }
-
-        Ok(Robot { rules, delay, sitemaps })
-    }
+            // Exceeding the limit fails closed by a Disallow rule matching
+            let rule =
+                rule.with_scan_limit(options.match_scan_limit, !is_allowed);
+            if rule.is_regex() {
+                compiled += 1;
+            }
 
-    fn prepare_url(raw_url: &str) -> String {
-        // Try to get only the path + query of the URL
-        if raw_url.is_empty() {
-            return "/".to_string();
+            rules.push(Rule {
+                regex: rule,
+                original,
+                allowed: is_allowed,
+                priority: 0,
+                line: numbers[*number - 1],
+            });
         }
-        // Note: If this fails we assume the passed URL is valid
-        // i.e. We assume the user has passed us a valid relative URL
-        let parsed = Url::parse(raw_url);
-        let url = match parsed.as_ref() {
-            // The Url library performs percent encoding
-            Ok(url) => url[Position::BeforePath..].to_string(),
-            Err(_) => percent_encode(raw_url),
+
+        let precedence = precedence_order(&rules, Tiebreak::default());
+        let index = RuleIndex::new(&rules, &precedence);
+        let access = classify_access(&rules);
+        let combined = match options.combine_regex_rules {
+            true => combine_rules(&rules, options.regex_size_limit),
+            false => vec![],
         };
-        url
+
+        Ok(Robot {
+            rules,
+            delay,
+            request_rate,
+            sitemaps,
+            host,
+            unparsed,
+            visit_time,
+            clean_params,
+            noindex,
+            warnings,
+            precedence,
+            index,
+            tiebreak: Tiebreak::default(),
+            access,
+            combined,
+            regex_size_limit: options.regex_size_limit,
+            matched_agent,
+            normalize_percent_encoding: options.normalize_percent_encoding,
+            index_as_directory: options.index_as_directory,
+        })
     }
 
-    /// Check if the given URL is allowed for the agent by `robots.txt`.
-    /// This function returns true or false according to the rules in `robots.txt`.
+    /// Find only the crawl delay for the agent without constructing a Robot.
+    ///
+    /// This avoids the cost of preparing the rules when only the delay is needed.
+    /// The delay is found as it would be by [Robot::new] with `None` also
+    /// returned if `robots.txt` fails to parse.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"User-Agent: Ferris\nCrawl-Delay: 10\nUser-Agent: *\nCrawl-Delay: 1";
+    /// assert_eq!(Robot::delay_for(txt, "Ferris"), Some(10.0));
+    /// assert_eq!(Robot::delay_for(txt, "Bob"), Some(1.0));
+    /// ```
+    pub fn delay_for(txt: &[u8], agent: &str) -> Option<f32> {
+        let txt = replace_nulls(truncate_input(txt, DEFAULT_MAX_BYTES));
+        let (_, lines) = robots_txt_parse(&txt).ok()?;
+        let lines = delay_lines(lines);
+        let (subset, _) = group_for_agent(&agent.to_lowercase(), &lines);
+        crawl_delay(&subset, &lines)
+    }
+
+    /// Construct a new Robot which follows Googlebot's interpretation of
+    /// `robots.txt` as closely as possible.
+    ///
+    /// This is a named preset for parity testing against Google's reference
+    /// parser. Beyond the defaults of [Robot::new] (longest match precedence
+    /// where an Allow wins a tie, only a final `$` anchoring a rule, and the
+    /// 500 kibibyte input limit) the preset enables:
+    ///
+    /// - [match_agent_product_token](RobotBuilder::match_agent_product_token)
+    ///   so a full User-Agent string (i.e. `Googlebot/2.1 (+http://...)`)
+    ///   finds the group for `Googlebot`
+    /// - [treat_index_as_directory](RobotBuilder::treat_index_as_directory)
+    ///   so an allowed `/dir/index.html` also allows `/dir/`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"User-Agent: Googlebot\nAllow: /ocean/index.html\nDisallow: /";
+    /// let agent = "Googlebot/2.1 (+http://www.google.com/bot.html)";
+    /// let r = Robot::new_googlebot_compatible(agent, txt).unwrap();
+    /// assert_eq!(r.allowed("/ocean/"), true);
+    /// assert_eq!(r.allowed("/desert"), false);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [Robot::new].
+    pub fn new_googlebot_compatible(
+        agent: &str,
+        txt: &[u8],
+    ) -> Result<Self, anyhow::Error> {
+        RobotBuilder::new(agent)
+            .match_agent_product_token(true)
+            .treat_index_as_directory(true)
+            .build(txt)
+    }
+
+    /// Construct a new Robot from already parsed rules without `robots.txt`.
+    ///
+    /// Each rule is `(pattern, is_allowed)` as returned by [Robot::rules] and
+    /// is prepared as [Robot::new] would (i.e. percent encoded and simplified).
+    /// Empty patterns are skipped as they match nothing.
+    ///
+    /// # Errors
+    ///
+    /// If a pattern fails to compile an error is returned as in [Robot::new].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let rules = vec![("/".to_string(), false), ("/ocean".to_string(), true)];
+    /// let r = Robot::from_rules(rules, Some(2.0), vec![]).unwrap();
+    /// assert!(r.allowed("/ocean"));
+    /// assert!(!r.allowed("/desert"));
+    /// assert_eq!(r.delay, Some(2.0));
+    /// ```
+    pub fn from_rules(
+        rules: impl IntoIterator<Item = (String, bool)>,
+        delay: Option<f32>,
+        sitemaps: Vec<String>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut prepared = vec![];
+        for (pat, is_allowed) in rules {
+            if pat.is_empty() {
+                continue;
+            }
+            let encoded = percent_encode(&pat);
+            let regex = match RobotRegex::new(&encoded) {
+                Ok(regex) => regex,
+                Err(e) => {
+                    let err = Error::RuleTooComplex { pattern: encoded };
+                    return Err(anyhow::Error::new(e).context(err));
+                }
+            };
+            prepared.push(Rule {
+                regex,
+                original: Some(pat).filter(|pat| *pat != encoded),
+                allowed: is_allowed,
+                priority: 0,
+                line: 0,
+            });
+        }
+
+        let precedence = precedence_order(&prepared, Tiebreak::default());
+        Ok(Robot {
+            index: RuleIndex::new(&prepared, &precedence),
+            precedence,
+            tiebreak: Tiebreak::default(),
+            access: classify_access(&prepared),
+            rules: prepared,
+            delay,
+            request_rate: None,
+            sitemaps,
+            host: None,
+            unparsed: 0,
+            visit_time: None,
+            clean_params: vec![],
+            noindex: vec![],
+            warnings: vec![],
+            combined: vec![],
+            regex_size_limit: DEFAULT_REGEX_SIZE_LIMIT,
+            matched_agent: None,
+            normalize_percent_encoding: false,
+            index_as_directory: false,
+        })
+    }
+
+    /// Choose how ties between matching rules of equal pattern length and the
+    /// same type are broken, which decides the rule reported for a URL.
+    ///
+    /// Crawlers differ here so this allows experimenting with which matches
+    /// their behavior. Defaults to [Tiebreak::FirstWins].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::{Robot, Tiebreak};
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /a*b\nDisallow: /a/b").unwrap();
+    /// assert_eq!(r.allowed_explain("/a/b/").pattern, Some("/a*b"));
+    /// let r = r.with_longest_match_tiebreak(Tiebreak::MostSpecificSegments);
+    /// assert_eq!(r.allowed_explain("/a/b/").pattern, Some("/a/b"));
+    /// ```
+    pub fn with_longest_match_tiebreak(mut self, tiebreak: Tiebreak) -> Self {
+        self.tiebreak = tiebreak;
+        self.precedence = precedence_order(&self.rules, tiebreak);
+        self.index = RuleIndex::new(&self.rules, &self.precedence);
+        self
+    }
+
+    /// Layer the rules of `override_robot` on top of this Robot's rules.
+    ///
+    /// This models layered policies such as a platform wide default with
+    /// site specific overrides. The resolution order for a given URL is:
+    ///
+    /// 1. If any rule from `override_robot` matches, the override decides
+    ///    using the usual longest match precedence among its own rules
+    /// 2. Otherwise the base rules decide using longest match precedence
+    /// 3. If no rule matches at all, the URL is allowed
+    ///
+    /// A short override rule therefore beats a longer base rule. The crawl
+    /// delay, request rate, and host of the override are used if set, falling
+    /// back to the base, and the sitemaps and clean params of both are
+    /// combined without duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let base = Robot::new("Ferris", b"Disallow: /blog/drafts").unwrap();
+    /// let site = Robot::new("Ferris", b"Allow: /blog").unwrap();
+    /// let r = base.overlay(&site);
+    /// assert_eq!(r.allowed("/blog/drafts/post.html"), true);
+    /// ```
+    pub fn overlay(&self, override_robot: &Robot) -> Robot {
+        // Raise the override above every base priority so it also composes with prior overlays
+        let base_priority =
+            self.rules.iter().map(|rule| rule.priority + 1).max().unwrap_or(0);
+        let rules = override_robot.rules.iter().map(|rule| Rule {
+            priority: rule.priority + base_priority,
+            ..rule.clone()
+        });
+        let mut robot = self.combine(override_robot, rules);
+        robot.delay = override_robot.delay.or(self.delay);
+        robot.request_rate = override_robot.request_rate.or(self.request_rate);
+        robot.host = override_robot.host.clone().or_else(|| self.host.clone());
+        robot.visit_time = override_robot.visit_time.or(self.visit_time);
+        robot.matched_agent = override_robot
+            .matched_agent
+            .clone()
+            .or_else(|| self.matched_agent.clone());
+        robot
+    }
+
+    /// Combine the rules of this Robot with those of `other` as though they
+    /// came from a single `robots.txt` (i.e. fetched from several mirrors).
+    ///
+    /// The rules of both apply with the usual longest match precedence, the
+    /// larger crawl delay is used, and the sitemaps of both are combined in
+    /// order without duplicates.
+    ///
+    /// Merging assumes both Robots were constructed for the same agent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let a = Robot::new("Ferris", b"Disallow: /rust\nCrawl-Delay: 2").unwrap();
+    /// let b = Robot::new("Ferris", b"Disallow: /forest\nCrawl-Delay: 5").unwrap();
+    /// let r = a.merge(&b);
+    /// assert_eq!(r.allowed("/rust"), false);
+    /// assert_eq!(r.allowed("/forest"), false);
+    /// assert_eq!(r.delay, Some(5.0));
+    /// ```
+    pub fn merge(&self, other: &Robot) -> Robot {
+        let slowest = |a: Option<f32>, b: Option<f32>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let mut robot = self.combine(other, other.rules.iter().cloned());
+        robot.delay = slowest(self.delay, other.delay);
+        robot.request_rate = slowest(self.request_rate, other.request_rate);
+        robot.host = self.host.clone().or_else(|| other.host.clone());
+        robot.visit_time = self.visit_time.or(other.visit_time);
+        robot.matched_agent =
+            self.matched_agent.clone().or_else(|| other.matched_agent.clone());
+        robot
+    }
+
+    // The parts of overlay and merge in common: this Robot with the given
+    // rules of `other` added and the lists of both combined without
+    // duplicates, leaving the caller to choose between the delay, host, etc.
+    fn combine(
+        &self,
+        other: &Robot,
+        rules: impl IntoIterator<Item = Rule>,
+    ) -> Robot {
+        let mut robot = self.clone();
+        robot.rules.extend(rules);
+        for sitemap in &other.sitemaps {
+            if !robot.sitemaps.contains(sitemap) {
+                robot.sitemaps.push(sitemap.clone());
+            }
+        }
+        for clean_param in &other.clean_params {
+            if !robot.clean_params.contains(clean_param) {
+                robot.clean_params.push(clean_param.clone());
+            }
+        }
+        for noindex in &other.noindex {
+            if !robot.noindex.contains(noindex) {
+                robot.noindex.push(noindex.clone());
+            }
+        }
+        robot.unparsed += other.unparsed;
+        robot.warnings.extend(other.warnings.iter().cloned());
+        robot.regex_size_limit =
+            self.regex_size_limit.max(other.regex_size_limit);
+        robot.index_as_directory =
+            self.index_as_directory || other.index_as_directory;
+        // Rule indices have changed so the precedence, access, and any combined rules must be rebuilt
+        robot.precedence = precedence_order(&robot.rules, robot.tiebreak);
+        robot.index = RuleIndex::new(&robot.rules, &robot.precedence);
+        robot.access = classify_access(&robot.rules);
+        if !self.combined.is_empty() || !other.combined.is_empty() {
+            robot.combined =
+                combine_rules(&robot.rules, robot.regex_size_limit);
+        }
+        robot
+    }
+
+    /// Check if the given URL is allowed when `robots.txt` was fetched from `origin`.
+    ///
+    /// A `robots.txt` file only governs the scheme, host, and port it was fetched
+    /// from. Absolute URLs belonging to any other origin are rejected while
+    /// relative URLs are assumed to belong to the origin. An `origin` that fails
+    /// to parse rejects all URLs.
+    ///
+    /// Hosts are compared case insensitively, a trailing dot is ignored, and a
+    /// default port is equivalent to no port (i.e. `http://example.com:80`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// let origin = "https://example.com";
+    /// assert_eq!(r.allowed_for_origin(origin, "https://EXAMPLE.com./public"), true);
+    /// assert_eq!(r.allowed_for_origin(origin, "https://example.com/secret"), false);
+    /// assert_eq!(r.allowed_for_origin(origin, "https://example.org/public"), false);
+    /// assert_eq!(r.allowed_for_origin(origin, "http://example.com/public"), false);
+    /// ```
+    pub fn allowed_for_origin(&self, origin: &str, url: &str) -> bool {
+        match Url::parse(origin) {
+            Ok(origin) => is_same_origin(&origin, url) && self.allowed(url),
+            Err(_) => false,
+        }
+    }
+
+    /// Filter a batch of URLs spanning many hosts down to those crawlable
+    /// given this Robot was fetched from `origin`.
+    ///
+    /// URLs belonging to another origin pass through untouched as this
+    /// `robots.txt` has no say over them. URLs on the origin, including
+    /// relative URLs, are only kept if allowed. If `origin` fails to parse
+    /// then only relative URLs are checked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// let urls = [
+    ///     "https://example.com/public",
+    ///     "https://example.com/secret",
+    ///     "https://example.org/secret",
+    /// ];
+    /// let kept: Vec<_> =
+    ///     r.filter_for_origin("https://example.com", urls.into_iter()).collect();
+    /// assert_eq!(kept, vec!["https://example.com/public", "https://example.org/secret"]);
+    /// ```
+    pub fn filter_for_origin<'a, 's>(
+        &'s self,
+        origin: &str,
+        urls: impl Iterator<Item = &'a str> + 's,
+    ) -> impl Iterator<Item = &'a str> + 's {
+        let origin = Url::parse(origin).ok();
+        urls.filter(move |url| {
+            let on_origin = match (&origin, Url::parse(url)) {
+                (Some(origin), Ok(_)) => is_same_origin(origin, url),
+                (None, Ok(_)) => false,
+                (_, Err(_)) => true,
+            };
+            !on_origin || self.allowed(url)
+        })
+    }
+
+    /// Check if the given path is allowed for both the `http` and `https`
+    /// variants of the host, assuming both serve this same `robots.txt`.
+    ///
+    /// Matching never considers the scheme, so a Robot built from the `http`
+    /// `robots.txt` says nothing about the `https` origin unless both serve the
+    /// same file. To guard against checking a URL against the wrong origin,
+    /// absolute URLs are rejected by returning `None` rather than an answer.
+    /// Use [Robot::allowed_for_origin] for absolute URLs instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// assert_eq!(r.allowed_both_schemes("/public"), Some(true));
+    /// assert_eq!(r.allowed_both_schemes("/secret"), Some(false));
+    /// assert_eq!(r.allowed_both_schemes("https://example.com/public"), None);
+    /// ```
+    pub fn allowed_both_schemes(&self, path: &str) -> Option<bool> {
+        match Url::parse(path) {
+            Ok(_) => None,
+            Err(_) => Some(self.allowed(path)),
+        }
+    }
+
+    /// Check if the given URL is allowed after removing the named query parameters.
+    ///
+    /// This allows rules keyed on meaningful query parameters to still apply
+    /// while ignoring noise such as tracking parameters. A name ending in `*`
+    /// removes all parameters starting with that prefix (i.e. `utm_*`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /*?utm_source=").unwrap();
+    /// assert_eq!(r.allowed("/page?utm_source=feed"), false);
+    /// assert_eq!(r.allowed_stripping_params("/page?utm_source=feed", &["utm_*"]), true);
+    /// ```
+    pub fn allowed_stripping_params(
+        &self,
+        url: &str,
+        params_to_strip: &[&str],
+    ) -> bool {
+        let url = Self::prepare_url(url);
+        let url = strip_query_params(&url, |name| {
+            params_to_strip.iter().any(|param| match param.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == *param,
+            })
+        });
+        self.allowed_path(&url)
+    }
+
+    /// Remove the query parameters made irrelevant by `Clean-param` directives.
+    ///
+    /// A directive applies if it has no path prefix or the URL's path starts
+    /// with it (wildcards are matched as in rules). The URL is reduced to its
+    /// path and query as in [allowed](Robot::allowed) and the order of the
+    /// remaining parameters is kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Clean-param: ref /forum").unwrap();
+    /// assert_eq!(r.strip_clean_params("/forum/post?ref=abc&id=5"), "/forum/post?id=5");
+    /// assert_eq!(r.strip_clean_params("/blog/post?ref=abc"), "/blog/post?ref=abc");
+    /// ```
+    pub fn strip_clean_params(&self, url: &str) -> String {
+        let url = Self::prepare_url(url);
+        let path = url.split(['?', '#']).next().unwrap_or(&url);
+        let params: Vec<&str> = self
+            .clean_params
+            .iter()
+            .filter(|clean| match &clean.path_prefix {
+                Some(prefix) => RobotRegex::new(prefix)
+                    .map(|prefix| prefix.is_match(path))
+                    .unwrap_or(false),
+                None => true,
+            })
+            .flat_map(|clean| clean.params.iter().map(|p| p.as_str()))
+            .collect();
+        if params.is_empty() {
+            return url;
+        }
+        strip_query_params(&url, |name| params.contains(&name))
+    }
+
+    /// Check whether `robots.txt` imposes no restrictions on this agent at all.
+    ///
+    /// This is true when there are no rules, only Allow rules, or when every
+    /// Disallow rule is overridden by an Allow rule matching all paths (such as
+    /// the `Allow: /` produced by an empty `Disallow:`). When true there is no
+    /// need to call [allowed](Robot::allowed) for each URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow:").unwrap();
+    /// assert_eq!(r.allows_everything(), true);
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// assert_eq!(r.allows_everything(), false);
+    /// ```
+    pub fn allows_everything(&self) -> bool {
+        self.access == Access::Allowed
+    }
+
+    /// Classify whether this agent may crawl everything, nothing, or only some
+    /// URLs. This is computed once when the Robot is constructed.
+    ///
+    /// [Access::Allowed] is given as for [allows_everything](Robot::allows_everything)
+    /// while [Access::Disallowed] is given when a rule such as `Disallow: /`
+    /// blocks every path and there are no Allow rules that could override it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::{Access, Robot};
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow:").unwrap();
+    /// assert_eq!(r.access(), Access::Allowed);
+    /// let r = Robot::new("Ferris", b"Disallow: /").unwrap();
+    /// assert_eq!(r.access(), Access::Disallowed);
+    /// let r = Robot::new("Ferris", b"Disallow: /\nAllow: /ocean").unwrap();
+    /// assert_eq!(r.access(), Access::Restricted);
+    /// ```
+    pub fn access(&self) -> Access {
+        self.access
+    }
+
+    /// Consume the Robot returning the rule patterns, the crawl delay, and the sitemaps.
+    ///
+    /// Rules are returned as `(pattern, is_allowed)` in the order they appeared
+    /// in `robots.txt`. Patterns are percent encoded as they are when matching.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret\nCrawl-Delay: 2").unwrap();
+    /// let (rules, delay, sitemaps) = r.into_parts();
+    /// assert_eq!(rules, vec![("/secret".to_string(), false)]);
+    /// assert_eq!(delay, Some(2.0));
+    /// assert!(sitemaps.is_empty());
+    /// ```
+    pub fn into_parts(
+        self,
+    ) -> (Vec<(String, bool)>, Option<f32>, Vec<String>) {
+        let rules = self
+            .rules
+            .into_iter()
+            .map(|rule| (rule.regex.into_pattern(), rule.allowed))
+            .collect();
+        (rules, self.delay, self.sitemaps)
+    }
+
+    /// The number of rules applying to this agent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /rust\nAllow: /ocean").unwrap();
+    /// assert_eq!(r.rule_count(), 2);
+    /// ```
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether there are no rules, no crawl delay, and no sitemaps.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// assert!(Robot::new("Ferris", b"# Nothing to see here").unwrap().is_empty());
+    /// assert!(!Robot::new("Ferris", b"Crawl-Delay: 1").unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+            && self.delay.is_none()
+            && self.sitemaps.is_empty()
+    }
+
+    /// The rules applying to this agent as `(pattern, is_allowed)`.
+    ///
+    /// Rules are given in the order they appeared in `robots.txt`.
+    /// Patterns are percent encoded as they are when matching.
+    pub fn rules(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.rules.iter().map(|rule| (rule.regex.pattern(), rule.allowed))
+    }
+
+    /// The patterns of the Disallow rules applying to this agent.
+    ///
+    /// These are in the same order and form as [Robot::rules].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"Disallow: /rust\nAllow: /rust/ferris\nDisallow: /ocean";
+    /// let r = Robot::new("Ferris", txt).unwrap();
+    /// assert_eq!(r.disallowed_paths().collect::<Vec<_>>(), ["/rust", "/ocean"]);
+    /// assert_eq!(r.allowed_paths().collect::<Vec<_>>(), ["/rust/ferris"]);
+    /// ```
+    pub fn disallowed_paths(&self) -> impl Iterator<Item = &str> {
+        self.rules()
+            .filter(|(_, allowed)| !allowed)
+            .map(|(pattern, _)| pattern)
+    }
+
+    /// The patterns of the Allow rules applying to this agent.
+    ///
+    /// See [Robot::disallowed_paths].
+    pub fn allowed_paths(&self) -> impl Iterator<Item = &str> {
+        self.rules()
+            .filter(|(_, allowed)| *allowed)
+            .map(|(pattern, _)| pattern)
+    }
+
+    /// The rules applying to this agent as `(line, pattern, is_allowed)` where
+    /// `line` is the line number (starting from 1) the rule came from.
+    ///
+    /// Rules are given in the same order and form as [Robot::rules]. Robots
+    /// combined with [overlay](Robot::overlay) or [merge](Robot::merge) keep the
+    /// line numbers from their own `robots.txt`. Rules given to
+    /// [from_rules](Robot::from_rules) have no source and so have a line of 0.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "User-Agent: *\n# Keep out\nDisallow: /secret\nAllow: /secret/public";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// let rules: Vec<_> = r.rules_with_lines().collect();
+    /// assert_eq!(rules, vec![(3, "/secret", false), (4, "/secret/public", true)]);
+    /// ```
+    pub fn rules_with_lines(
+        &self,
+    ) -> impl Iterator<Item = (usize, &str, bool)> {
+        self.rules
+            .iter()
+            .map(|rule| (rule.line, rule.regex.pattern(), rule.allowed))
+    }
+
+    /// The rules applying to this agent as `(pattern, is_allowed)` with
+    /// patterns as written in `robots.txt` rather than percent encoded.
+    ///
+    /// This is intended for displaying rules. Matching still uses the
+    /// percent encoded patterns given by [Robot::rules].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", "Disallow: /ツ".as_bytes()).unwrap();
+    /// assert_eq!(r.rules().next(), Some(("/%E3%83%84", false)));
+    /// assert_eq!(r.rules_original().next(), Some(("/ツ", false)));
+    /// ```
+    pub fn rules_original(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.rules.iter().map(|rule| {
+            let pattern = rule
+                .original
+                .as_deref()
+                .unwrap_or_else(|| rule.regex.pattern());
+            (pattern, rule.allowed)
+        })
+    }
+
+    /// The Allow rules that carve out an exception under a Disallow rule as
+    /// `(disallow_pattern, allow_pattern)` pairs.
+    ///
+    /// An Allow rule is an exception when it's longer than the Disallow rule
+    /// and starts with the Disallow rule's literal prefix (the pattern up to
+    /// any `*` or `$`).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "Disallow: /admin
+    /// Allow: /admin/public
+    /// Allow: /blog";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(r.allow_exceptions(), vec![("/admin", "/admin/public")]);
+    /// ```
+    pub fn allow_exceptions(&self) -> Vec<(&str, &str)> {
+        let mut exceptions = vec![];
+        for (disallow, _) in self.rules().filter(|(_, allowed)| !allowed) {
+            let prefix = match disallow.find(['*', '$']) {
+                Some(idx) => &disallow[..idx],
+                None => disallow,
+            };
+            for (allow, _) in self.rules().filter(|(_, allowed)| *allowed) {
+                if allow.len() > disallow.len() && allow.starts_with(prefix) {
+                    exceptions.push((disallow, allow));
+                }
+            }
+        }
+        exceptions
+    }
+
+    /// The rules whose pattern starts with the literal `prefix`.
+    ///
+    /// This answers which rules govern a section of the site (i.e. `/api`)
+    /// without testing individual URLs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "Disallow: /api/private
+    /// Allow: /api/public
+    /// Disallow: /admin";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(
+    ///     r.rules_under("/api"),
+    ///     vec![("/api/private", false), ("/api/public", true)]
+    /// );
+    /// ```
+    pub fn rules_under(&self, prefix: &str) -> Vec<(&str, bool)> {
+        self.rules()
+            .filter(|(pattern, _)| pattern.starts_with(prefix))
+            .collect()
+    }
+
+    /// The preferred crawl window from the `Visit-time` directive for this agent.
+    ///
+    /// This is advisory only and does not affect [allowed](Robot::allowed).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::{Robot, VisitTime};
+    ///
+    /// let r = Robot::new("Ferris", b"Visit-time: 0600-0845").unwrap();
+    /// assert_eq!(r.visit_time(), Some(VisitTime { start: (6, 0), end: (8, 45) }));
+    /// ```
+    pub fn visit_time(&self) -> Option<VisitTime> {
+        self.visit_time
+    }
+
+    /// The crawl delay rounded to the nearest millisecond.
+    ///
+    /// Delays too large to represent saturate at `u64::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Crawl-Delay: 0.25").unwrap();
+    /// assert_eq!(r.delay_millis(), Some(250));
+    /// ```
+    pub fn delay_millis(&self) -> Option<u64> {
+        // Float to integer casts saturate (with NaN becoming zero)
+        self.delay.map(|d| (f64::from(d) * 1000.0).round() as u64)
+    }
+
+    /// The crawl delay as a [Duration].
+    ///
+    /// Delays too large to represent (or infinite) saturate at [Duration::MAX].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Crawl-Delay: 2.5").unwrap();
+    /// assert_eq!(r.delay_duration(), Some(Duration::from_millis(2500)));
+    /// ```
+    pub fn delay_duration(&self) -> Option<Duration> {
+        self.delay
+            .map(|d| Duration::try_from_secs_f32(d).unwrap_or(Duration::MAX))
+    }
+
+    /// The number of bytes of `robots.txt` left unconsumed by the parser.
+    ///
+    /// This should always be zero as the parser is highly forgiving. A non-zero
+    /// value indicates a parser edge case worth investigating.
+    pub fn unparsed_bytes(&self) -> usize {
+        self.unparsed
+    }
+
+    /// The User-Agent token, as written in `robots.txt`, of the group that
+    /// matched this agent.
+    ///
+    /// This is `*` when falling back to the catch all group and `None` when
+    /// no group matched.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("googlebot", b"User-Agent: GoogleBot\nDisallow: /").unwrap();
+    /// assert_eq!(r.matched_agent_original(), Some("GoogleBot"));
+    /// ```
+    pub fn matched_agent_original(&self) -> Option<&str> {
+        self.matched_agent.as_deref()
+    }
+
+    /// Whether `robots.txt` has a group naming this agent rather than only
+    /// the catch all agent (`*`).
+    ///
+    /// Useful for deciding whether a site explicitly addresses your crawler.
+    /// See [Robot::matched_agent_original] for the name as written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"User-Agent: FerrisCrawler\nDisallow: /\nUser-Agent: *\nAllow: /";
+    /// assert!(Robot::new("FerrisCrawler", txt).unwrap().agent_is_named());
+    /// assert!(!Robot::new("BobBot", txt).unwrap().agent_is_named());
+    /// ```
+    pub fn agent_is_named(&self) -> bool {
+        !matches!(self.matched_agent_original(), None | Some("*"))
+    }
+
+    /// The query parameters declared irrelevant by `Clean-param` directives.
+    ///
+    /// Like sitemaps these aren't tied to any user agent. Malformed entries
+    /// are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::{CleanParam, Robot};
+    ///
+    /// let r = Robot::new("Ferris", b"Clean-param: ref&sid /forum").unwrap();
+    /// assert_eq!(
+    ///     r.clean_params(),
+    ///     [CleanParam {
+    ///         params: vec!["ref".to_string(), "sid".to_string()],
+    ///         path_prefix: Some("/forum".to_string()),
+    ///     }]
+    /// );
+    /// ```
+    pub fn clean_params(&self) -> &[CleanParam] {
+        &self.clean_params
+    }
+
+    /// The patterns of any `Noindex` directives for this agent.
+    ///
+    /// `Noindex` is no longer supported by Google but is still read by some
+    /// crawlers. These hints don't affect [allowed](Robot::allowed).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Noindex: /drafts").unwrap();
+    /// assert_eq!(r.noindex_rules().collect::<Vec<_>>(), vec!["/drafts"]);
+    /// assert_eq!(r.allowed("/drafts"), true);
+    /// ```
+    pub fn noindex_rules(&self) -> impl Iterator<Item = &str> {
+        self.noindex.iter().map(|pat| pat.as_str())
+    }
+
+    /// Problems found in `robots.txt` that didn't prevent parsing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::{Robot, WarningKind};
+    ///
+    /// let txt = "<!DOCTYPE html>\n<html><body>Not Found</body></html>";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(r.warnings()[0].kind, WarningKind::LooksLikeHtml);
+    /// ```
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    // Get the path + query of a parsed URL normalized as in prepare_url
+    fn prepare_parsed_url(url: &Url) -> String {
+        uppercase_percent_hex(url[Position::BeforePath..].to_string())
+    }
+
+    fn prepare_url(raw_url: &str) -> String {
+        // Try to get only the path + query of the URL
+        if raw_url.is_empty() {
+            return "/".to_string();
+        }
+        // Note: If this fails we assume the passed URL is valid
+        // i.e. We assume the user has passed us a valid relative URL
+        let parsed = match raw_url.starts_with("//") {
+            // A protocol relative URL (i.e. "//example.com/a") starts with an
+            // authority rather than a path so it's parsed as if it had a scheme
+            true => Url::parse(&format!("http:{}", raw_url)),
+            false => Url::parse(raw_url),
+        };
+        let url = match parsed.as_ref() {
+            // The Url library performs percent encoding
+            Ok(url) => Self::prepare_parsed_url(url),
+            Err(_) => percent_encode(raw_url),
+        };
+        url
+    }
+
+    /// Check if the given URL is allowed for the agent by `robots.txt`.
+    /// This function returns true or false according to the rules in `robots.txt`.
     ///
     /// The provided URL can be absolute or relative depending on user preference.
+    /// A protocol relative URL (i.e. `//example.com/secret`) is matched by its
+    /// path.
+    ///
+    /// Percent encoded characters in the URL (i.e. `%0A`, `%00`, or `%23`) are
+    /// only ever matched as literal path content and are never decoded or
+    /// interpreted as `robots.txt` syntax. Only unreserved characters are
+    /// decoded if [normalize_percent_encoding](RobotBuilder::normalize_percent_encoding)
+    /// is set. The hex digits of encoded octets are uppercased in both rules
+    /// and URLs so `%aa` matches `%AA`.
     ///
     /// # Example
     ///
@@ -531,29 +3017,598 @@ impl Robot {
     /// assert_eq!(r.allowed("https://example.com/secret"), false);
     /// assert_eq!(r.allowed("/secret"), false);
     /// assert_eq!(r.allowed("/everything-else"), true);
+    /// assert_eq!(r.allowed("//example.com/secret"), false);
     /// ```
     pub fn allowed(&self, url: &str) -> bool {
-        let url = Self::prepare_url(url);
+        self.allowed_path(&Self::prepare_url(url))
+    }
+
+    /// Check if the given pre-parsed URL is allowed for the agent by `robots.txt`.
+    ///
+    /// This is equivalent to [allowed](Robot::allowed) but avoids converting
+    /// the URL back to a string and parsing it again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    /// use url::Url;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// let url = Url::parse("https://example.com/secret?q=1").unwrap();
+    /// assert_eq!(r.allowed_url(&url), false);
+    /// ```
+    pub fn allowed_url(&self, url: &Url) -> bool {
+        self.allowed_path(&Self::prepare_parsed_url(url))
+    }
+
+    /// Check if the given URL is allowed as in [allowed](Robot::allowed) but
+    /// returning `default` when no rule matches.
+    ///
+    /// This allows a "deny unless explicitly allowed" policy. `/robots.txt`
+    /// is always allowed regardless of `default`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Allow: /public").unwrap();
+    /// assert_eq!(r.allowed_with_default("/public/index.html", false), true);
+    /// assert_eq!(r.allowed_with_default("/private", false), false);
+    /// assert_eq!(r.allowed_with_default("/robots.txt", false), true);
+    /// ```
+    pub fn allowed_with_default(&self, url: &str, default: bool) -> bool {
+        self.allowed_path_with_default(&Self::prepare_url(url), default)
+    }
+
+    // Check the path + query of a prepared URL
+    fn allowed_path(&self, url: &str) -> bool {
+        // If there are no rules we assume we're allowed
+        self.allowed_path_with_default(url, true)
+    }
+
+    fn allowed_path_with_default(&self, url: &str, default: bool) -> bool {
         if url == "/robots.txt" {
             return true;
         }
 
-        // Filter to only rules matching the URL
-        let mut matches: Vec<&_> = self
-            .rules
-            .iter()
-            .filter(|(rule, _)| rule.is_match(&url))
-            .collect();
+        match self.matching_rule(url) {
+            Some(rule) => rule.allowed,
+            None => default,
+        }
+    }
+
+    /// Check whether each of the given URLs is allowed for the agent by `robots.txt`.
+    ///
+    /// The results are in the same order as the input URLs. This is equivalent
+    /// to calling [allowed](Robot::allowed) on each URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// let urls = ["/", "/secret", "https://example.com/secret/plans"];
+    /// assert_eq!(r.allowed_many(urls), vec![true, false, false]);
+    /// ```
+    pub fn allowed_many<'a>(
+        &self,
+        urls: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<bool> {
+        // As precedence is precomputed the first matching rule is found without
+        // collecting candidates so there's no working memory to reuse
+        urls.into_iter().map(|url| self.allowed(url)).collect()
+    }
+
+    /// Check if each of the given pre-parsed URLs is allowed, in order.
+    ///
+    /// This is equivalent to calling [allowed_url](Robot::allowed_url) for
+    /// each URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    /// use url::Url;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// let urls = ["https://example.com/secret", "https://example.com/public"]
+    ///     .map(|url| Url::parse(url).unwrap());
+    /// assert_eq!(r.allowed_batch_url(&urls), [false, true]);
+    /// ```
+    pub fn allowed_batch_url<'a>(
+        &self,
+        urls: impl IntoIterator<Item = &'a Url>,
+    ) -> Vec<bool> {
+        urls.into_iter().map(|url| self.allowed_url(url)).collect()
+    }
 
-        // Sort according to the longest match and then by whether it's allowed
-        // RobotRegex is sorted with preference going from longest to shortest
-        // If there are two rules of equal length, allow and disallow, spec says allow
-        matches.sort_by_key(|x| (&x.0, !x.1));
+    /// Check if the given URL is allowed or is a declared sitemap.
+    ///
+    /// Sitemaps declared in `robots.txt` are always accessible, even if the
+    /// file mistakenly disallows their path, so any URL matching a declared
+    /// sitemap is allowed. Other URLs are checked as by [Robot::allowed].
+    /// To find such mistakes see [Robot::sitemap_allowed].
+    ///
+    /// An absolute URL must match the sitemap's scheme, host, and port while
+    /// a relative URL only needs to match its path and query.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = b"Disallow: /maps\nSitemap: https://example.com/maps/sitemap.xml";
+    /// let r = Robot::new("Ferris", txt).unwrap();
+    /// assert_eq!(r.allowed("/maps/sitemap.xml"), false);
+    /// assert_eq!(r.allowed_or_sitemap("https://example.com/maps/sitemap.xml"), true);
+    /// assert_eq!(r.allowed_or_sitemap("/maps/sitemap.xml"), true);
+    /// assert_eq!(r.allowed_or_sitemap("/maps/other.xml"), false);
+    /// ```
+    pub fn allowed_or_sitemap(&self, url: &str) -> bool {
+        let path = Self::prepare_url(url);
+        let parsed = Url::parse(url).ok();
+        let declared = self.sitemaps.iter().any(|sitemap| {
+            let sitemap = match Url::parse(sitemap) {
+                Ok(sitemap) => sitemap,
+                Err(_) => return false,
+            };
+            let same_origin =
+                parsed.as_ref().is_none_or(|u| u.origin() == sitemap.origin());
+            same_origin && Self::prepare_url(sitemap.as_str()) == path
+        });
+        declared || self.allowed_path(&path)
+    }
 
-        match matches.first() {
-            Some((_, is_allowed)) => *is_allowed,
+    /// Check if the given URL is allowed and explain which rule decided it.
+    ///
+    /// The pattern and directive are `None` when no rule matched, or for
+    /// `/robots.txt` which is always allowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::{Directive, Robot};
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /\nAllow: /ocean").unwrap();
+    /// let decision = r.allowed_explain("/desert");
+    /// assert_eq!(decision.allowed, false);
+    /// assert_eq!(decision.pattern, Some("/"));
+    /// assert_eq!(decision.directive, Some(Directive::Disallow));
+    /// assert_eq!(decision.matched_len, Some(1));
+    /// ```
+    pub fn allowed_explain(&self, url: &str) -> Decision<'_> {
+        let url = Self::prepare_url(url);
+        let rule = match url == "/robots.txt" {
+            true => None,
+            false => self.matching_rule_with_path(&url),
+        };
+        match rule {
+            Some((rule, url)) => Decision {
+                allowed: rule.allowed,
+                pattern: Some(rule.regex.pattern()),
+                directive: Some(match rule.allowed {
+                    true => Directive::Allow,
+                    false => Directive::Disallow,
+                }),
+                // The URL is matched as in matching_rule
+                matched_len: match self.normalize_percent_encoding {
+                    true => {
+                        rule.regex.matches_prefix_len(&decode_unreserved(&url))
+                    }
+                    false => rule.regex.matches_prefix_len(&url),
+                },
+                kind: Some(rule.regex.kind()),
+            },
             // If there are no rules we assume we're allowed
-            None => true,
+            None => Decision {
+                allowed: true,
+                pattern: None,
+                directive: None,
+                matched_len: None,
+                kind: None,
+            },
+        }
+    }
+
+    /// The rule deciding whether the given URL is allowed as
+    /// `(pattern, is_allowed)` in the form given by [Robot::rules].
+    ///
+    /// This is the rule found by [allowed_explain](Robot::allowed_explain)
+    /// and is `None` if no rule matched or for `/robots.txt`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /\nAllow: /ocean").unwrap();
+    /// assert_eq!(r.longest_matching_rule("/ocean/deep"), Some(("/ocean", true)));
+    /// assert_eq!(r.longest_matching_rule("/desert"), Some(("/", false)));
+    /// assert_eq!(r.longest_matching_rule("/robots.txt"), None);
+    /// ```
+    pub fn longest_matching_rule(&self, url: &str) -> Option<(&str, bool)> {
+        let url = Self::prepare_url(url);
+        if url == "/robots.txt" {
+            return None;
+        }
+        self.matching_rule(&url)
+            .map(|rule| (rule.regex.pattern(), rule.allowed))
+    }
+
+    // The rules in the order they take precedence when matching
+    #[cfg_attr(not(feature = "dot"), allow(dead_code))]
+    fn rules_by_precedence(&self) -> Vec<&Rule> {
+        self.precedence.iter().map(|&idx| &self.rules[idx]).collect()
+    }
+
+    // The index of the first rule in order of precedence that matches the URL
+    // Only rules whose first path segment could match the URL are checked
+    fn first_match(
+        &self,
+        url: &str,
+        include: impl Fn(&Rule) -> bool,
+    ) -> Option<usize> {
+        // Ranks are in order of precedence so the first match of each list wins
+        self.index
+            .candidates(url)
+            .iter()
+            .filter_map(|ranks| {
+                ranks.iter().copied().find(|&rank| {
+                    let rule = &self.rules[self.precedence[rank]];
+                    include(rule) && rule.regex.is_match(url)
+                })
+            })
+            .min()
+            .map(|rank| self.precedence[rank])
+    }
+
+    // Find the rule deciding whether the prepared URL is allowed
+    fn matching_rule(&self, url: &str) -> Option<&Rule> {
+        self.matching_rule_with_path(url).map(|(rule, _)| rule)
+    }
+
+    // As matching_rule but also giving the path the rule matched, which for
+    // an index page or directory may differ (see RobotBuilder)
+    fn matching_rule_with_path<'u>(
+        &self,
+        url: &'u str,
+    ) -> Option<(&Rule, Cow<'u, str>)> {
+        let rule = self.matching_rule_exact(url);
+        if !self.index_as_directory {
+            return rule.map(|rule| (rule, Cow::Borrowed(url)));
+        }
+
+        // As Google does an allowed index page also allows its directory
+        // This acts as the rule "/dir/$" for an Allow of "/dir/index.html"
+        let index_rule =
+            self.index.index_pages.get(url).map(|&idx| &self.rules[idx]);
+        if let Some(index_rule) = index_rule {
+            let key = |rule: &Rule, len: usize| {
+                (Reverse(rule.priority), Reverse(len))
+            };
+            let wins = rule.is_none_or(|rule| {
+                key(index_rule, url.len() + 1)
+                    <= key(rule, rule.regex.pattern().len())
+            });
+            if wins {
+                let page = index_rule.regex.pattern().to_string();
+                return Some((index_rule, Cow::Owned(page)));
+            }
+        }
+
+        // Without a matching rule an index page is checked as its directory
+        match rule {
+            Some(rule) => Some((rule, Cow::Borrowed(url))),
+            None => {
+                let directory = index_directory(url)?;
+                let rule = self.matching_rule_exact(directory)?;
+                Some((rule, Cow::Borrowed(directory)))
+            }
+        }
+    }
+
+    // Find the rule deciding whether the prepared URL itself is allowed
+    fn matching_rule_exact(&self, url: &str) -> Option<&Rule> {
+        let normalized;
+        let url = match self.normalize_percent_encoding {
+            true => {
+                normalized = decode_unreserved(url);
+                normalized.as_str()
+            }
+            false => url,
+        };
+        if self.combined.is_empty() {
+            return self
+                .first_match(url, |_| true)
+                .map(|idx| &self.rules[idx]);
+        }
+
+        // Regex rules are checked by a single scan of each combined set
+        let first = self
+            .first_match(url, |rule| !rule.regex.is_regex())
+            .map(|idx| (idx, &self.rules[idx]));
+        let regex_matches = self.combined.iter().flat_map(|combined| {
+            let rules = &combined.rules;
+            combined.set.matches(url).into_iter().map(move |idx| rules[idx])
+        });
+        first
+            .into_iter()
+            .chain(regex_matches.map(|idx| (idx, &self.rules[idx])))
+            .min_by_key(|(idx, rule)| {
+                (rule.precedence(), self.tiebreak.key(&self.rules, *idx))
+            })
+            .map(|(_, rule)| rule)
+    }
+
+    /// Check if the given URL is allowed and bundle the crawl delay to honor.
+    ///
+    /// This forces both outcomes to be handled, with the delay only given when
+    /// the URL may be fetched. Delays too large to represent saturate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use texting_robots::{Gate, Robot};
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret\nCrawl-Delay: 2").unwrap();
+    /// assert_eq!(r.gate("/public"), Gate::Allowed { delay: Some(Duration::from_secs(2)) });
+    /// assert_eq!(r.gate("/secret"), Gate::Blocked { rule: "/secret".to_string() });
+    /// ```
+    pub fn gate(&self, url: &str) -> Gate {
+        let url = Self::prepare_url(url);
+        if url != "/robots.txt" {
+            if let Some(rule) = self.matching_rule(&url) {
+                if !rule.allowed {
+                    let rule = rule.regex.pattern().to_string();
+                    return Gate::Blocked { rule };
+                }
+            }
+        }
+        Gate::Allowed { delay: self.delay_duration() }
+    }
+
+    /// Describe the decision for the given URL in the style of Google's
+    /// Search Console `robots.txt` tester.
+    ///
+    /// The result is `ALLOWED` or `BLOCKED` followed by the deciding rule if
+    /// there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "Disallow: /bar
+    /// Allow: /bar/foo";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(r.tester_report("/bar/foo"), r#"ALLOWED by "Allow: /bar/foo""#);
+    /// assert_eq!(r.tester_report("/bar"), r#"BLOCKED by "Disallow: /bar""#);
+    /// assert_eq!(r.tester_report("/baz"), "ALLOWED");
+    /// ```
+    pub fn tester_report(&self, url: &str) -> String {
+        let decision = self.allowed_explain(url);
+        match (decision.directive, decision.pattern) {
+            (Some(Directive::Allow), Some(pattern)) => {
+                format!("ALLOWED by \"Allow: {}\"", pattern)
+            }
+            (Some(Directive::Disallow), Some(pattern)) => {
+                format!("BLOCKED by \"Disallow: {}\"", pattern)
+            }
+            _ => "ALLOWED".to_string(),
+        }
+    }
+
+    /// Check if the given URL is allowed for the agent by `robots.txt` after
+    /// ensuring the path begins with exactly one leading slash.
+    ///
+    /// Relative inputs such as `foo`, `//foo`, and `///foo` are all treated
+    /// as the path `/foo` rather than depending on how the URL parser
+    /// interprets the leading slashes. Absolute URLs are reduced to their
+    /// path and query as in [allowed](Robot::allowed) before the leading
+    /// slashes are collapsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Disallow: /secret").unwrap();
+    /// assert_eq!(r.allowed_normalized("secret"), false);
+    /// assert_eq!(r.allowed_normalized("//secret"), false);
+    /// assert_eq!(r.allowed_normalized("https://example.com//secret"), false);
+    /// ```
+    pub fn allowed_normalized(&self, url: &str) -> bool {
+        // Only absolute URLs are prepared first as "//foo" would otherwise
+        // be open to interpretation as a host rather than a path
+        let url = match Url::parse(url) {
+            Ok(_) => Self::prepare_url(url),
+            Err(_) => url.to_string(),
+        };
+        let url = format!("/{}", url.trim_start_matches('/'));
+        self.allowed(&url)
+    }
+
+    /// Add sitemaps discovered outside of `robots.txt` (i.e. from an HTML
+    /// `<link rel="sitemap">`), skipping any that are already present.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let r = Robot::new("Ferris", b"Sitemap: https://example.com/a.xml").unwrap();
+    /// let r = r.with_sitemaps(vec![
+    ///     "https://example.com/a.xml".to_string(),
+    ///     "https://example.com/b.xml".to_string(),
+    /// ]);
+    /// assert_eq!(r.sitemaps, vec!["https://example.com/a.xml", "https://example.com/b.xml"]);
+    /// ```
+    pub fn with_sitemaps(mut self, extra: Vec<String>) -> Self {
+        for sitemap in extra {
+            if !self.sitemaps.contains(&sitemap) {
+                self.sitemaps.push(sitemap);
+            }
+        }
+        self
+    }
+
+    /// Resolve the sitemaps against `base` (i.e. the URL of `robots.txt`)
+    /// returning only those that are valid `http` or `https` URLs.
+    ///
+    /// Any text after whitespace in an entry is ignored. The raw entries
+    /// remain available in [sitemaps](Robot::sitemaps).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    /// use url::Url;
+    ///
+    /// let txt = "Sitemap: https://example.com/a.xml
+    /// Sitemap: /b.xml
+    /// Sitemap: ftp://example.com/c.xml";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// let base = Url::parse("https://example.com/robots.txt").unwrap();
+    /// let urls: Vec<String> = r.sitemap_urls(&base).iter().map(|u| u.to_string()).collect();
+    /// assert_eq!(urls, vec!["https://example.com/a.xml", "https://example.com/b.xml"]);
+    /// ```
+    pub fn sitemap_urls(&self, base: &Url) -> Vec<Url> {
+        self.sitemaps
+            .iter()
+            .filter_map(|sitemap| sitemap.split_whitespace().next())
+            .filter_map(|sitemap| base.join(sitemap).ok())
+            .filter(|url| {
+                matches!(url.scheme(), "http" | "https") && url.has_host()
+            })
+            .collect()
+    }
+
+    /// The sitemaps with their path and query percent encoded so they can
+    /// be fetched directly.
+    ///
+    /// Characters already percent encoded are left unchanged. The raw entries
+    /// remain available in [sitemaps](Robot::sitemaps).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "Sitemap: https://ja.example.org/テスト.xml
+    /// Sitemap: https://example.com/%E3%83%84.xml";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(r.sitemaps_encoded(), vec![
+    ///     "https://ja.example.org/%E3%83%86%E3%82%B9%E3%83%88.xml",
+    ///     "https://example.com/%E3%83%84.xml",
+    /// ]);
+    /// ```
+    pub fn sitemaps_encoded(&self) -> Vec<String> {
+        self.sitemaps
+            .iter()
+            .map(|sitemap| {
+                // Only the path onwards is encoded, leaving the scheme and host
+                let host_start = sitemap.find("://").map_or(0, |idx| idx + 3);
+                let path_start = sitemap[host_start..]
+                    .find('/')
+                    .map_or(sitemap.len(), |idx| host_start + idx);
+                let (origin, path) = sitemap.split_at(path_start);
+                format!("{}{}", origin, percent_encode(path))
+            })
+            .collect()
+    }
+
+    /// The sitemaps with each distinct entry given once in the order first
+    /// seen.
+    ///
+    /// Entries are compared exactly as written in `robots.txt`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "Sitemap: https://example.com/b.xml
+    /// Sitemap: https://example.com/a.xml
+    /// Sitemap: https://example.com/b.xml";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(r.sitemaps_deduplicated(), vec![
+    ///     "https://example.com/b.xml",
+    ///     "https://example.com/a.xml",
+    /// ]);
+    /// ```
+    pub fn sitemaps_deduplicated(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.sitemaps
+            .iter()
+            .map(String::as_str)
+            .filter(|sitemap| seen.insert(*sitemap))
+            .collect()
+    }
+
+    /// Return the sitemaps whose URL parses with the given scheme.
+    ///
+    /// Relative or otherwise malformed sitemap entries are excluded as are
+    /// sitemaps with any other scheme. The scheme comparison is case insensitive.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "Sitemap: http://example.com/old.xml
+    /// Sitemap: https://example.com/new.xml";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(r.sitemaps_with_scheme("http"), vec!["http://example.com/old.xml"]);
+    /// ```
+    pub fn sitemaps_with_scheme(&self, scheme: &str) -> Vec<String> {
+        self.sitemaps
+            .iter()
+            .filter(|sitemap| match Url::parse(sitemap) {
+                Ok(url) => url.scheme().eq_ignore_ascii_case(scheme),
+                Err(_) => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Check whether the sitemap at the given index of [sitemaps](Robot::sitemaps)
+    /// would itself be allowed by the crawl rules.
+    ///
+    /// Per the specification a sitemap listed in `robots.txt` may be fetched
+    /// regardless of the rules. Some sites list sitemaps under disallowed paths
+    /// however and this flags such inconsistencies.
+    ///
+    /// Returns `None` if the index is out of range or the sitemap isn't an
+    /// absolute URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use texting_robots::Robot;
+    ///
+    /// let txt = "Disallow: /private
+    /// Sitemap: https://example.com/private/sitemap.xml";
+    /// let r = Robot::new("Ferris", txt.as_bytes()).unwrap();
+    /// assert_eq!(r.sitemap_allowed(0), Some(false));
+    /// assert_eq!(r.sitemap_allowed(1), None);
+    /// ```
+    pub fn sitemap_allowed(&self, index: usize) -> Option<bool> {
+        let sitemap = self.sitemaps.get(index)?;
+        match Url::parse(sitemap) {
+            Ok(_) => Some(self.allowed(sitemap)),
+            Err(_) => None,
         }
     }
+
+    /// Return only the sitemaps served over HTTPS.
+    ///
+    /// This is equivalent to [sitemaps_with_scheme](Robot::sitemaps_with_scheme)
+    /// with `"https"`.
+    pub fn https_sitemaps(&self) -> Vec<String> {
+        self.sitemaps_with_scheme("https")
+    }
 }
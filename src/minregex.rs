@@ -1,8 +1,25 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
 
-use bstr::ByteSlice;
 use lazy_static::lazy_static;
 use regex::{Error, Regex, RegexBuilder};
+use texting_robots_matching::{
+    match_stars_anchored_within, match_stars_within, StarMatch,
+};
+
+use crate::RuleKind;
+
+// Anchored patterns with more "*" than this skip the regex and use match_stars
+// Each ".*" adds to the compiled size so many groups risk exceeding the size limit
+const MAX_REGEX_STARS: usize = 8;
+
+// Computation / memory limits applied to each regex against adversarial actors
+// This was previously 10KB but was upped to 42KB due to real domains with complex regexes
+pub const DEFAULT_REGEX_SIZE_LIMIT: usize = 42 * (1 << 10);
+
+// Bytes of text match_stars may scan for a single match before giving up
+// Matching is linear so this only triggers for enormous URLs
+pub const DEFAULT_MATCH_SCAN_LIMIT: usize = 1 << 20;
 
 #[derive(Debug, Clone)]
 pub struct MinRegex {
@@ -10,6 +27,18 @@ pub struct MinRegex {
     // The regex is only constructed if the pattern contains "*" or "$"
     regex: Option<Regex>,
     starred: Option<String>,
+    // Suffix rules of the form "prefix*suffix$" (i.e. "/*.php$") are checked
+    // directly as (prefix, suffix) rather than through a full regex
+    anchored: Option<(String, String)>,
+    // Anchored rules with too many "*" for a regex (see MAX_REGEX_STARS) keep
+    // their body without the "$" which must match to the end of the text
+    anchored_starred: Option<String>,
+    // Patterns and text are compared ignoring ASCII case if set
+    case_insensitive: bool,
+    // match_stars stops after scanning this many bytes of the text
+    scan_limit: usize,
+    // Whether text exceeding the scan limit is taken as matching
+    match_beyond_limit: bool,
 }
 
 impl Ord for MinRegex {
@@ -36,86 +65,274 @@ impl Eq for MinRegex {}
 
 impl MinRegex {
     pub fn new(pattern: &str) -> Result<Self, Error> {
-        // If the pattern doesn't contain "*" or "$" it's just a "starts_with" check.
+        Self::new_with_case(pattern, false)
+    }
+
+    /// Construct the rule optionally ignoring case when matching
+    /// (i.e. "/Admin" also matches "/admin")
+    pub fn new_with_case(
+        pattern: &str,
+        case_insensitive: bool,
+    ) -> Result<Self, Error> {
+        Self::new_with_limit(
+            pattern,
+            case_insensitive,
+            DEFAULT_REGEX_SIZE_LIMIT,
+        )
+    }
+
+    /// Construct the rule with any regex compiled within `size_limit` bytes
+    /// Patterns whose regex exceeds the limit are matched without one
+    pub fn new_with_limit(
+        pattern: &str,
+        case_insensitive: bool,
+        size_limit: usize,
+    ) -> Result<Self, Error> {
+        let mut pat = Self::normalize(pattern);
+        // The fast paths compare against the lowercased text
+        if case_insensitive {
+            pat.make_ascii_lowercase();
+        }
+
+        // Only a "$" at the end of the pattern is an anchor
+        // Any other "$" (i.e. "/foo$bar") is matched literally as in a URL
+        let anchored = pat.ends_with('$');
+
+        // If the pattern doesn't contain "*" or end in "$" it's just a "starts_with" check.
         // We avoid compiling the regex as it's slow and takes space
-        if !pattern.contains('$') && !pattern.contains('*') {
+        if !anchored && !pat.contains('*') {
             return Ok(Self {
                 pattern: pattern.to_string(),
                 regex: None,
-                starred: None,
+                // Only needed if normalizing changed the pattern (i.e. "/a*")
+                starred: if pat != pattern { Some(pat) } else { None },
+                anchored: None,
+                anchored_starred: None,
+                case_insensitive,
+                scan_limit: DEFAULT_MATCH_SCAN_LIMIT,
+                match_beyond_limit: false,
             });
         }
-        // TODO: We should ensure that "$" only appears at the end of the pattern
         // TODO: We could implement "$" w/o "*" using "starts_with" and "equal to".
 
-        // Replace any long runs of "*" with a single "*"
-        // The two regexes "x.*y" and "x.*.*y" are equivalent but not simplified by the regex parser
-        // Given that rules like "x***********y" exist this prevents memory blow-up in the regex
-        lazy_static! {
-            static ref STARKILLER_REGEX: Regex = Regex::new(r"\*+").unwrap();
-        }
-        let pat = STARKILLER_REGEX.replace_all(pattern, "*");
-
-        // If the pattern contains "$" we must do a proper regular expression to ensure it matches
+        // If the pattern ends in "$" we must do a proper regular expression to ensure it matches
         // Otherwise we can do a shortcut of ensuring each section is sequentially contained in the target
         // See: match_stars
-        if !pattern.contains('$') {
+        if !anchored {
+            return Ok(Self {
+                pattern: pattern.to_string(),
+                regex: None,
+                starred: Some(pat),
+                anchored: None,
+                anchored_starred: None,
+                case_insensitive,
+                scan_limit: DEFAULT_MATCH_SCAN_LIMIT,
+                match_beyond_limit: false,
+            });
+        }
+
+        // Rules such as "/*.php$" are really "starts with / and ends with .php"
+        // These are common enough to avoid compiling a regex for
+        if let Some((prefix, suffix)) = Self::anchored_parts(&pat) {
             return Ok(Self {
                 pattern: pattern.to_string(),
                 regex: None,
-                starred: Some(pat.to_string()),
+                starred: None,
+                anchored: Some((prefix.to_string(), suffix.to_string())),
+                anchored_starred: None,
+                case_insensitive,
+                scan_limit: DEFAULT_MATCH_SCAN_LIMIT,
+                match_beyond_limit: false,
             });
         }
 
-        // Escape the pattern (except for the * and $ specific operators) for use in regular expressions
-        let pat = regex::escape(&pat).replace("\\*", ".*").replace("\\$", "$");
+        let body = &pat[..pat.len() - 1];
+        let anchored_starred = Self {
+            pattern: pattern.to_string(),
+            regex: None,
+            starred: None,
+            anchored: None,
+            anchored_starred: Some(body.to_string()),
+            case_insensitive,
+            scan_limit: DEFAULT_MATCH_SCAN_LIMIT,
+            match_beyond_limit: false,
+        };
+        if body.matches('*').count() > MAX_REGEX_STARS {
+            return Ok(anchored_starred);
+        }
+
+        // Escape the pattern (except for the * operator) for use in regular expressions
+        let pat = regex::escape(body).replace("\\*", ".*");
         // We prepend with ^ to ensure it doesn't find a matching substring later in the URL
         // See: test_robot_handles_starting_position
-        let pat = "^".to_string() + &pat;
+        let pat = "^".to_string() + &pat + "$";
 
         let rule = RegexBuilder::new(&pat)
             // Apply computation / memory limits against adversarial actors
-            // This was previously 10KB but was upped to 42KB due to real domains with complex regexes
-            .dfa_size_limit(42 * (1 << 10))
-            .size_limit(42 * (1 << 10))
-            .build()?;
+            .dfa_size_limit(size_limit)
+            .size_limit(size_limit)
+            .case_insensitive(case_insensitive)
+            .build();
+
+        match rule {
+            Ok(rule) => Ok(Self {
+                pattern: pattern.to_string(),
+                regex: Some(rule),
+                starred: None,
+                anchored: None,
+                anchored_starred: None,
+                case_insensitive,
+                scan_limit: DEFAULT_MATCH_SCAN_LIMIT,
+                match_beyond_limit: false,
+            }),
+            // Rather than failing we match without the regex
+            Err(Error::CompiledTooBig(_)) => Ok(anchored_starred),
+            Err(e) => Err(e),
+        }
+    }
 
-        Ok(Self {
+    /// Limit the bytes of text scanned by match_stars for a single match
+    /// Beyond the limit the text is taken as matching if `match_beyond_limit`
+    /// (i.e. so a Disallow rule fails closed) and otherwise as not matching
+    pub fn with_scan_limit(
+        mut self,
+        scan_limit: usize,
+        match_beyond_limit: bool,
+    ) -> Self {
+        self.scan_limit = scan_limit;
+        self.match_beyond_limit = match_beyond_limit;
+        self
+    }
+
+    // Split a normalized "prefix*suffix$" pattern into its prefix and suffix
+    fn anchored_parts(pat: &str) -> Option<(&str, &str)> {
+        let body = pat.strip_suffix('$')?;
+        if body.matches('*').count() != 1 {
+            return None;
+        }
+        body.split_once('*')
+    }
+
+    /// Whether constructing the rule would compile a full regex
+    pub fn requires_regex(pattern: &str) -> bool {
+        let pat = Self::normalize(pattern);
+        pat.ends_with('$')
+            && Self::anchored_parts(&pat).is_none()
+            && pat.matches('*').count() <= MAX_REGEX_STARS
+    }
+
+    /// Construct the rule matching only the literal prefix of the pattern
+    /// (i.e. "/a/*/b$" matches anything starting with "/a/")
+    pub fn literal_prefix(pattern: &str, case_insensitive: bool) -> Self {
+        // The prefix is taken as matched (i.e. "?q" is "*?q" so has none)
+        let pat = Self::normalize(pattern);
+        let prefix = pat.strip_suffix('$').unwrap_or(&pat);
+        let prefix = match prefix.find('*') {
+            Some(idx) => &prefix[..idx],
+            None => prefix,
+        };
+        let mut prefix = prefix.to_string();
+        if case_insensitive {
+            prefix.make_ascii_lowercase();
+        }
+        Self {
             pattern: pattern.to_string(),
-            regex: Some(rule),
-            starred: None,
-        })
+            regex: None,
+            // Without any "*" match_stars is a "starts_with" check
+            starred: Some(prefix),
+            anchored: None,
+            anchored_starred: None,
+            case_insensitive,
+            scan_limit: DEFAULT_MATCH_SCAN_LIMIT,
+            match_beyond_limit: false,
+        }
     }
 
-    pub fn match_stars(&self, pattern: &[u8], text: &[u8]) -> bool {
-        // Break the pattern into the parts between the "*"
-        let parts = pattern.as_bytes().split(|&b| b == b'*');
+    /// Reduce the pattern to the simplest equivalent form used for matching.
+    pub fn normalize(pattern: &str) -> String {
+        // Note: "*" already matches across "/" so the "**" convention used by
+        // some files to mean "across slashes" is equivalent to "*"
+        // Replace any long runs of "*" with a single "*"
+        // The two regexes "x.*y" and "x.*.*y" are equivalent but not simplified by the regex parser
+        // Given that rules like "x***********y" exist this prevents memory blow-up in the regex
+        lazy_static! {
+            static ref STARKILLER_REGEX: Regex = Regex::new(r"\*+").unwrap();
+        }
+        // A lone "$" would match only an empty path which no URL has
+        // As the closest useful meaning we treat it as the root only ("/$")
+        if pattern == "$" {
+            return "/$".to_string();
+        }
+        // Most patterns are a plain prefix and need no work
+        if !pattern.contains('*') && !pattern.starts_with('?') {
+            return pattern.to_string();
+        }
+        let mut pat = STARKILLER_REGEX.replace_all(pattern, "*").into_owned();
 
-        let mut starting_point = 0;
+        // A rule of only a query string (i.e. "?replytocom") is intended to
+        // match that query on any path, hence we treat it as "*?replytocom"
+        if pat.starts_with('?') {
+            pat.insert(0, '*');
+        }
 
-        for (idx, part) in parts.enumerate() {
-            if idx == 0 && !text.is_empty() && text[0] != b'*' {
-                // The first part is special if it doesn't start with a '*'
-                // This must match at the very start
-                if !text.starts_with(part) {
-                    return false;
+        // A trailing "*" or "*$" allows any ending and so is redundant
+        // Unless removing it would leave a literal "$" at the end as an anchor
+        // (i.e. "/a$*" matches "/a$x" but "/a$" would match only "/a")
+        for redundant in ["*$", "*"] {
+            if let Some(rest) = pat.strip_suffix(redundant) {
+                if !rest.is_empty() && !rest.ends_with('$') {
+                    pat.truncate(rest.len());
                 }
-                starting_point += part.len();
-                continue;
+                break;
             }
+        }
+        pat
+    }
 
-            match text[starting_point..].find(part) {
-                Some(idx) => {
-                    starting_point += idx + part.len();
-                }
-                None => return false,
+    pub fn match_stars(&self, pattern: &[u8], text: &[u8]) -> bool {
+        self.match_stars_end(pattern, text).is_some()
+    }
+
+    // As match_stars but giving the end of the match in the text
+    fn match_stars_end(&self, pattern: &[u8], text: &[u8]) -> Option<usize> {
+        let matched = match_stars_within(pattern, text, self.scan_limit);
+        self.beyond_limit(matched, text)
+    }
+
+    // As match_stars but the pattern must match until the end of the text
+    pub fn match_stars_anchored(&self, pattern: &[u8], text: &[u8]) -> bool {
+        let matched =
+            match_stars_anchored_within(pattern, text, self.scan_limit);
+        self.beyond_limit(matched, text).is_some()
+    }
+
+    // The end of the match taking the whole text as matched if the scan limit
+    // was exceeded and the rule should then match (see with_scan_limit)
+    fn beyond_limit(&self, matched: StarMatch, text: &[u8]) -> Option<usize> {
+        match matched {
+            StarMatch::Matched(end) => Some(end),
+            StarMatch::NotMatched => None,
+            StarMatch::LimitExceeded => {
+                self.match_beyond_limit.then_some(text.len())
             }
         }
-
-        true
     }
 
     pub fn is_match(&self, text: &str) -> bool {
+        let text = match self.case_insensitive && self.regex.is_none() {
+            true => Cow::Owned(text.to_ascii_lowercase()),
+            false => Cow::Borrowed(text),
+        };
+        let text = text.as_ref();
+        if let Some((prefix, suffix)) = &self.anchored {
+            return text.len() >= prefix.len() + suffix.len()
+                && text.starts_with(prefix.as_str())
+                && text.ends_with(suffix.as_str());
+        }
+        if let Some(body) = &self.anchored_starred {
+            return self
+                .match_stars_anchored(body.as_bytes(), text.as_bytes());
+        }
         match &self.regex {
             Some(r) => r.is_match(text),
             None => match &self.starred {
@@ -125,9 +342,94 @@ impl MinRegex {
         }
     }
 
+    /// The length in bytes of the text matched by the rule, if it matches.
+    ///
+    /// Unlike the pattern length this is the actual extent of the match
+    /// (i.e. "/fish*.php" matching "/fish/a.php?q" consumes "/fish/a.php")
+    pub fn matches_prefix_len(&self, text: &str) -> Option<usize> {
+        if !self.is_match(text) {
+            return None;
+        }
+        // Anchored rules always match until the end of the text
+        if self.regex.is_some()
+            || self.anchored.is_some()
+            || self.anchored_starred.is_some()
+        {
+            return Some(text.len());
+        }
+        let text = match self.case_insensitive {
+            // Lowercasing only changes ASCII so the lengths are unchanged
+            true => Cow::Owned(text.to_ascii_lowercase()),
+            false => Cow::Borrowed(text),
+        };
+        match &self.starred {
+            Some(p) => self.match_stars_end(p.as_bytes(), text.as_bytes()),
+            None => Some(self.pattern.len()),
+        }
+    }
+
+    /// The first path segment any matching text must start with between
+    /// slashes (i.e. "a" for "/a/b*.php") if the pattern fixes one
+    pub fn first_segment(&self) -> Option<&str> {
+        // Case insensitive rules would need the text lowercased to look up
+        if self.case_insensitive {
+            return None;
+        }
+        let literal = self.pattern.strip_suffix('$').unwrap_or(&self.pattern);
+        let literal = literal.split('*').next()?;
+        let rest = literal.strip_prefix('/')?;
+        rest.find('/').map(|end| &rest[..end])
+    }
+
+    pub fn is_regex(&self) -> bool {
+        self.regex.is_some()
+    }
+
+    /// How the rule is matched after normalizing (i.e. "/a*" is a literal)
+    pub fn kind(&self) -> RuleKind {
+        let anchored = self.regex.is_some()
+            || self.anchored.is_some()
+            || self.anchored_starred.is_some();
+        match &self.starred {
+            _ if anchored => RuleKind::Anchored,
+            Some(starred) if starred.contains('*') => RuleKind::Wildcard,
+            _ => RuleKind::Literal,
+        }
+    }
+
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// The compiled regular expression if the rule required one
+    pub fn regex_str(&self) -> Option<&str> {
+        self.regex.as_ref().map(|r| r.as_str())
+    }
+
+    /// The original pattern the rule was constructed from
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Consume the rule returning the original pattern
+    pub fn into_pattern(self) -> String {
+        self.pattern
+    }
+
+    /// Whether the rule matches every path (i.e. "/", "*", "/*", or "/*$")
+    pub fn matches_everything(&self) -> bool {
+        // Normalizing reduces "/*" and "/*$" to "/" but keeps "*" and "*$"
+        let pat = Self::normalize(&self.pattern);
+        let rest = pat.strip_prefix('/').unwrap_or(&pat);
+        !pat.is_empty() && matches!(rest, "" | "*" | "*$")
+    }
+
     // Code is used in testing to ensure expected wildcard reduction
     #[allow(dead_code)]
     pub fn as_str(&self) -> &str {
+        if let Some(body) = &self.anchored_starred {
+            return body.as_str();
+        }
         match &self.regex {
             Some(r) => r.as_str(),
             None => match &self.starred {
@@ -2,22 +2,43 @@ use core::fmt;
 
 use bstr::ByteSlice;
 
-use nom::branch::{alt, Alt};
+use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case, take_while};
 use nom::character::complete::{space0, space1};
-use nom::combinator::{eof, opt};
-use nom::error::ParseError as NomParseError;
+use nom::combinator::{consumed, eof, map, opt, recognize};
 use nom::multi::many_till;
 use nom::sequence::preceded;
 use nom::IResult;
 
+/// A single line of `robots.txt` as tokenized by [parse_lines](crate::parse_lines).
+///
+/// Values borrow from the bytes given to [parse_lines](crate::parse_lines) and are trimmed
+/// of whitespace and comments but otherwise left as written (i.e. rules
+/// aren't percent encoded or normalized). No agent selection is performed.
 #[derive(PartialEq, Copy, Clone)]
+#[non_exhaustive]
 pub enum Line<'a> {
+    /// `User-Agent: <agent>`
     UserAgent(&'a [u8]),
+    /// `Allow: <pattern>`
     Allow(&'a [u8]),
+    /// `Disallow: <pattern>` (an empty pattern is given as `Allow: /`)
     Disallow(&'a [u8]),
+    /// `Noindex: <pattern>`
+    Noindex(&'a [u8]),
+    /// `Sitemap: <url>`
     Sitemap(&'a [u8]),
+    /// `Host: <host>`
+    Host(&'a [u8]),
+    /// `Clean-param: <params> [path]` with parameters separated by `&`
+    CleanParam { params: &'a [u8], path: Option<&'a [u8]> },
+    /// `Crawl-Delay: <seconds>` or `None` if the delay was invalid
     CrawlDelay(Option<f32>),
+    /// `Request-rate: <requests>/<time>` normalized to seconds per request
+    RequestRate(f32),
+    /// `Visit-time: <HHMM>-<HHMM>` given as `(hour, minute)` pairs
+    VisitTime { start: (u8, u8), end: (u8, u8) },
+    /// Any other line, including blank lines and comments
     Raw(&'a [u8]),
 }
 
@@ -34,12 +55,31 @@ impl fmt::Debug for Line<'_> {
             Line::Disallow(a) => {
                 f.debug_tuple("Disallow").field(&a.as_bstr()).finish()
             }
+            Line::Noindex(a) => {
+                f.debug_tuple("Noindex").field(&a.as_bstr()).finish()
+            }
             Line::CrawlDelay(c) => {
                 f.debug_tuple("CrawlDelay").field(&c).finish()
             }
+            Line::RequestRate(r) => {
+                f.debug_tuple("RequestRate").field(&r).finish()
+            }
+            Line::VisitTime { start, end } => f
+                .debug_struct("VisitTime")
+                .field("start", &start)
+                .field("end", &end)
+                .finish(),
             Line::Sitemap(sm) => {
                 f.debug_tuple("Sitemap").field(&sm.as_bstr()).finish()
             }
+            Line::Host(h) => {
+                f.debug_tuple("Host").field(&h.as_bstr()).finish()
+            }
+            Line::CleanParam { params, path } => f
+                .debug_struct("CleanParam")
+                .field("params", &params.as_bstr())
+                .field("path", &path.map(|p| p.as_bstr()))
+                .finish(),
             Line::Raw(r) => f.debug_tuple("Raw").field(&r.as_bstr()).finish(),
         }
     }
@@ -53,35 +93,25 @@ fn is_not_line_ending_or_comment(c: u8) -> bool {
     c != b'\n' && c != b'\r' && c != b'#'
 }
 
-fn is_carriage_return(c: u8) -> bool {
-    c == b'\r'
-}
-
 fn consume_newline(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
-    let (input, _) = take_while(is_carriage_return)(input)?;
-    let (input, output) = opt(tag(b"\n"))(input)?;
-    Ok((input, output))
+    // A line ends with "\r\n", "\n", or a lone "\r" (as in classic Mac OS files)
+    // Any extra "\r" before a "\n" (i.e. "\r\r\n" from a repeated conversion)
+    // is part of the same line ending
+    let crlf = recognize(preceded(take_while(|c| c == b'\r'), tag(b"\n")));
+    opt(alt((crlf, tag(b"\r"))))(input)
 }
 
-fn line(input: &[u8]) -> IResult<&[u8], Line> {
+fn line(input: &[u8]) -> IResult<&[u8], Line<'_>> {
     let (input, line) = take_while(is_not_line_ending)(input)?;
     let (input, _) = consume_newline(input)?;
     Ok((input, Line::Raw(line)))
 }
 
-fn many_statement_builder<
-    'a,
-    O,
-    E: NomParseError<&'a [u8]>,
-    List: Alt<&'a [u8], O, E>,
->(
+fn many_statement_builder<'a>(
     input: &'a [u8],
-    targets: List,
-) -> IResult<&'a [u8], &'a [u8]>
-where
-    nom::Err<nom::error::Error<&'a [u8]>>: From<nom::Err<E>>,
-{
-    let (input, _) = preceded(space0, alt(targets))(input)?;
+    keyword: impl FnMut(&'a [u8]) -> IResult<&'a [u8], &'a [u8]>,
+) -> IResult<&'a [u8], &'a [u8]> {
+    let (input, _) = preceded(space0, keyword)(input)?;
     // This accepts a colon with spaces ("Disallow: /a") or one or more spaces ("Disallow /")
     let (input, _) = alt((preceded(space0, tag(":")), space1))(input)?;
     let (input, line) = take_while(is_not_line_ending_or_comment)(input)?;
@@ -92,57 +122,128 @@ where
     Ok((input, line))
 }
 
-fn user_agent(input: &[u8]) -> IResult<&[u8], Line> {
-    let matcher = (
+fn user_agent_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    alt((
         tag_no_case("user-agent"),
         tag_no_case("user agent"),
         tag_no_case("useragent"),
-    );
-    let (input, agent) = many_statement_builder(input, matcher)?;
+    ))(input)
+}
+
+fn user_agent(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, agent) = many_statement_builder(input, user_agent_keyword)?;
     Ok((input, Line::UserAgent(agent)))
 }
 
-fn allow(input: &[u8]) -> IResult<&[u8], Line> {
-    let matcher = (tag_no_case("allow"),);
-    let (input, rule) = many_statement_builder(input, matcher)?;
+fn allow_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    tag_no_case("allow")(input)
+}
+
+fn allow(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, rule) = many_statement_builder(input, allow_keyword)?;
     Ok((input, Line::Allow(rule)))
 }
 
-fn disallow(input: &[u8]) -> IResult<&[u8], Line> {
-    let matcher = (
+fn disallow_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    alt((
         tag_no_case("disallow"),
         tag_no_case("dissallow"),
         tag_no_case("dissalow"),
         tag_no_case("disalow"),
         tag_no_case("diasllow"),
         tag_no_case("disallaw"),
-    );
-    let (input, rule) = many_statement_builder(input, matcher)?;
-    if rule.is_empty() {
+    ))(input)
+}
+
+fn disallow(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, rule) = many_statement_builder(input, disallow_keyword)?;
+    Ok((input, disallow_line(rule)))
+}
+
+fn disallow_line(rule: &[u8]) -> Line<'_> {
+    // A stray BOM or control characters can't form a rule so are also empty
+    let empty = rule
+        .trim_with(|c| c == '\u{feff}' || c.is_whitespace() || c.is_control())
+        .is_empty();
+    if empty {
         // "Disallow:" is equivalent to allow all
         // See: https://moz.com/learn/seo/robotstxt and RFC example
-        return Ok((input, Line::Allow(b"/")));
+        return Line::Allow(b"/");
     }
-    Ok((input, Line::Disallow(rule)))
+    Line::Disallow(rule)
+}
+
+fn noindex_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    tag_no_case("noindex")(input)
+}
+
+fn noindex(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, rule) = many_statement_builder(input, noindex_keyword)?;
+    Ok((input, Line::Noindex(rule)))
 }
 
-fn sitemap(input: &[u8]) -> IResult<&[u8], Line> {
-    let matcher = (
+fn sitemap_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    alt((
         tag_no_case("sitemap"),
         tag_no_case("site-map"),
         tag_no_case("site map"),
-    );
-    let (input, url) = many_statement_builder(input, matcher)?;
+    ))(input)
+}
+
+fn sitemap(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, url) = many_statement_builder(input, sitemap_keyword)?;
     Ok((input, Line::Sitemap(url)))
 }
 
-fn crawl_delay(input: &[u8]) -> IResult<&[u8], Line> {
-    let matcher = (
+fn host_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    tag_no_case("host")(input)
+}
+
+fn host(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, host) = many_statement_builder(input, host_keyword)?;
+    Ok((input, Line::Host(host)))
+}
+
+fn clean_param_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    alt((
+        tag_no_case("clean-param"),
+        tag_no_case("clean param"),
+        tag_no_case("cleanparam"),
+    ))(input)
+}
+
+fn clean_param(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, value) = many_statement_builder(input, clean_param_keyword)?;
+
+    // The value is in the form "p1&p2 /path" with the path being optional
+    let mut parts = value.fields();
+    let line = match (parts.next(), parts.next(), parts.next()) {
+        (Some(params), path, None)
+            if params.split(|&c| c == b'&').all(|p| !p.is_empty()) =>
+        {
+            Some(Line::CleanParam { params, path })
+        }
+        _ => None,
+    };
+    match line {
+        Some(line) => Ok((input, line)),
+        None => Err(nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::Fail,
+        })),
+    }
+}
+
+fn crawl_delay_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    alt((
         tag_no_case("crawl-delay"),
         tag_no_case("crawl delay"),
         tag_no_case("crawldelay"),
-    );
-    let (input, time) = many_statement_builder(input, matcher)?;
+    ))(input)
+}
+
+fn crawl_delay(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, time) = many_statement_builder(input, crawl_delay_keyword)?;
 
     let time = match std::str::from_utf8(time) {
         Ok(time) => time,
@@ -165,15 +266,171 @@ fn crawl_delay(input: &[u8]) -> IResult<&[u8], Line> {
     Ok((input, Line::CrawlDelay(delay)))
 }
 
-pub fn robots_txt_parse(input: &[u8]) -> IResult<&[u8], Vec<Line>> {
+fn request_rate_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    alt((
+        tag_no_case("request-rate"),
+        tag_no_case("request rate"),
+        tag_no_case("requestrate"),
+    ))(input)
+}
+
+// Parse "<documents>/<time>" where time has an optional unit (i.e. "1/10s")
+// Any trailing time window (i.e. "1/10s 0600-0845") is ignored
+fn parse_request_rate(rate: &[u8]) -> Option<f32> {
+    let rate = std::str::from_utf8(rate).ok()?.split_whitespace().next()?;
+    let (documents, time) = rate.split_once('/')?;
+    let documents = documents.trim().parse::<u32>().ok().filter(|d| *d > 0)?;
+    let time = time.trim();
+    let (time, unit) = match time.char_indices().last()? {
+        (idx, 's') | (idx, 'S') => (&time[..idx], 1.0),
+        (idx, 'm') | (idx, 'M') => (&time[..idx], 60.0),
+        (idx, 'h') | (idx, 'H') => (&time[..idx], 60.0 * 60.0),
+        (idx, 'd') | (idx, 'D') => (&time[..idx], 24.0 * 60.0 * 60.0),
+        _ => (time, 1.0),
+    };
+    let time = time.parse::<f32>().ok().filter(|t| *t >= 0.0)?;
+    Some(time * unit / documents as f32)
+}
+
+fn request_rate(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, rate) = many_statement_builder(input, request_rate_keyword)?;
+    match parse_request_rate(rate) {
+        Some(rate) => Ok((input, Line::RequestRate(rate))),
+        None => Err(nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::Digit,
+        })),
+    }
+}
+
+fn parse_hhmm(time: &[u8]) -> Option<(u8, u8)> {
+    if time.len() != 4 || !time.iter().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hour = (time[0] - b'0') * 10 + (time[1] - b'0');
+    let minute = (time[2] - b'0') * 10 + (time[3] - b'0');
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+    Some((hour, minute))
+}
+
+fn visit_time_keyword(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    alt((
+        tag_no_case("visit-time"),
+        tag_no_case("visit time"),
+        tag_no_case("visittime"),
+    ))(input)
+}
+
+fn visit_time(input: &[u8]) -> IResult<&[u8], Line<'_>> {
+    let (input, window) = many_statement_builder(input, visit_time_keyword)?;
+
+    // The window is in the form "HHMM-HHMM" (i.e. "0600-0845")
+    let mut parts = window.splitn(2, |&c| c == b'-');
+    let window = match (parts.next(), parts.next()) {
+        (Some(start), Some(end)) => {
+            parse_hhmm(start.trim()).zip(parse_hhmm(end.trim()))
+        }
+        _ => None,
+    };
+    match window {
+        Some((start, end)) => Ok((input, Line::VisitTime { start, end })),
+        None => Err(nom::Err::Error(nom::error::Error {
+            input,
+            code: nom::error::ErrorKind::Digit,
+        })),
+    }
+}
+
+// As many_statement_builder but "#" only starts a comment at the start of the
+// value or after whitespace (i.e. "/a#b # note" is "/a#b")
+fn literal_hash_statement_builder<'a>(
+    input: &'a [u8],
+    keyword: impl FnMut(&'a [u8]) -> IResult<&'a [u8], &'a [u8]>,
+) -> IResult<&'a [u8], &'a [u8]> {
+    let (input, _) = preceded(space0, keyword)(input)?;
+    let (input, _) = alt((preceded(space0, tag(":")), space1))(input)?;
+    let (input, line) = take_while(is_not_line_ending)(input)?;
+    let (input, _) = consume_newline(input)?;
+    let end = (0..line.len())
+        .find(|&idx| {
+            line[idx] == b'#'
+                && (idx == 0 || line[idx - 1].is_ascii_whitespace())
+        })
+        .unwrap_or(line.len());
+    Ok((input, line[..end].trim()))
+}
+
+/// Parse an Allow or Disallow line keeping any "#" within the rule.
+///
+/// A "#" is only treated as starting a comment at the start of the value or
+/// when following whitespace. Returns `None` for any other line.
+pub fn rule_with_literal_hash(line: &[u8]) -> Option<Line<'_>> {
+    if let Ok((_, rule)) = literal_hash_statement_builder(line, allow_keyword)
+    {
+        return Some(Line::Allow(rule));
+    }
+    literal_hash_statement_builder(line, disallow_keyword)
+        .ok()
+        .map(|(_, rule)| disallow_line(rule))
+}
+
+/// Find the directive keyword used by a line and its canonical spelling.
+///
+/// This allows reporting when one of the forgiven variations (i.e. "dissallow")
+/// was used rather than the directive's canonical spelling.
+pub fn directive_keyword(line: &[u8]) -> Option<(&[u8], &'static str)> {
+    let keywords = alt((
+        map(user_agent_keyword, |k| (k, "User-agent")),
+        map(allow_keyword, |k| (k, "Allow")),
+        map(disallow_keyword, |k| (k, "Disallow")),
+        map(noindex_keyword, |k| (k, "Noindex")),
+        map(sitemap_keyword, |k| (k, "Sitemap")),
+        map(host_keyword, |k| (k, "Host")),
+        map(clean_param_keyword, |k| (k, "Clean-param")),
+        map(crawl_delay_keyword, |k| (k, "Crawl-delay")),
+        map(request_rate_keyword, |k| (k, "Request-rate")),
+        map(visit_time_keyword, |k| (k, "Visit-time")),
+    ));
+    let result: IResult<&[u8], _> = preceded(space0, keywords)(line);
+    result.ok().map(|(_, keyword)| keyword)
+}
+
+pub fn robots_txt_parse(input: &[u8]) -> IResult<&[u8], Vec<Line<'_>>> {
+    let (input, lines) = robots_txt_parse_with_raw(input)?;
+    Ok((input, lines.into_iter().map(|(_, line)| line).collect()))
+}
+
+/// Parse `robots.txt` keeping the raw text each line was parsed from.
+pub fn robots_txt_parse_with_raw(
+    input: &[u8],
+) -> IResult<&[u8], Vec<(&[u8], Line<'_>)>> {
     // Remove BOM ("\xef\xbb\xbf", "\uFEFF") if present
     // TODO: Find a more elegant solution that shortcuts
     let (input, _) = opt(tag(b"\xef"))(input)?;
     let (input, _) = opt(tag(b"\xbb"))(input)?;
     let (input, _) = opt(tag(b"\xbf"))(input)?;
-    // TODO: Google limits to 500KB of data - should that be done here?
-    let matcher =
-        alt((user_agent, allow, disallow, sitemap, crawl_delay, line));
-    let (input, (lines, _)) = many_till(matcher, eof)(input)?;
+    // Note: Google's 500KB limit is applied before parsing (see Robot::new_with_limit)
+    lines_with_raw(input)
+}
+
+/// As robots_txt_parse_with_raw for lines after the start of the file
+/// (i.e. without removing a BOM) such as those streamed by RobotParser.
+pub fn lines_with_raw(input: &[u8]) -> IResult<&[u8], Vec<(&[u8], Line<'_>)>> {
+    let matcher = alt((
+        user_agent,
+        allow,
+        disallow,
+        noindex,
+        sitemap,
+        host,
+        clean_param,
+        crawl_delay,
+        request_rate,
+        visit_time,
+        line,
+    ));
+    let (input, (lines, _)) = many_till(consumed(matcher), eof)(input)?;
     Ok((input, lines))
 }
@@ -0,0 +1,72 @@
+/// How to treat the response when fetching `robots.txt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusAction {
+    /// Parse the payload as `robots.txt`.
+    Parse,
+    /// Follow the redirect if [RobotsFetchPolicy::should_follow_redirect] allows.
+    FollowRedirect,
+    /// Assume there are no crawl restrictions.
+    AllowAll,
+    /// Assume crawling isn't allowed until the server recovers.
+    DenyAll,
+    /// Retry fetching after a reasonable amount of time
+    /// (potentially set by the "Retry-After" header).
+    RetryAfter,
+}
+
+/// The suggested handling of HTTP responses when fetching `robots.txt`.
+///
+/// Fetching is left to the user of the library but the decisions follow
+/// [Google's recommendations](crate#obtaining-robotstxt):
+///
+/// - 2xx (success): Parse the payload
+/// - 3xx (redirection): Follow up to `max_redirects` redirects
+/// - 4xx (client error): Assume there are no crawl restrictions except for:
+///   - 429 "Too Many Requests": Retry after a reasonable amount of time
+/// - 5xx (server errors): Assume you should not crawl
+///
+/// Any other status code is treated cautiously as a server error.
+///
+/// ```rust
+/// use texting_robots::{RobotsFetchPolicy, StatusAction};
+///
+/// let policy = RobotsFetchPolicy::default();
+/// assert_eq!(policy.interpret_status(200), StatusAction::Parse);
+/// assert_eq!(policy.interpret_status(404), StatusAction::AllowAll);
+/// assert!(policy.should_follow_redirect(5));
+/// assert!(!policy.should_follow_redirect(6));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RobotsFetchPolicy {
+    /// The maximum number of redirects to follow.
+    pub max_redirects: u32,
+}
+
+impl Default for RobotsFetchPolicy {
+    fn default() -> Self {
+        RobotsFetchPolicy { max_redirects: 5 }
+    }
+}
+
+impl RobotsFetchPolicy {
+    pub fn new(max_redirects: u32) -> Self {
+        RobotsFetchPolicy { max_redirects }
+    }
+
+    /// Whether to follow a redirect where `hop` counts redirects from one
+    /// (i.e. the first redirect seen is hop one).
+    pub fn should_follow_redirect(&self, hop: u32) -> bool {
+        hop <= self.max_redirects
+    }
+
+    /// The action to take given the HTTP status code of the response.
+    pub fn interpret_status(&self, code: u16) -> StatusAction {
+        match code {
+            200..=299 => StatusAction::Parse,
+            300..=399 => StatusAction::FollowRedirect,
+            429 => StatusAction::RetryAfter,
+            400..=499 => StatusAction::AllowAll,
+            _ => StatusAction::DenyAll,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Robot;
+
+// Only the rules, delay, and sitemaps are kept with the rules recompiled on load
+#[derive(Serialize)]
+struct RobotRef<'a> {
+    rules: Vec<(&'a str, bool)>,
+    delay: Option<f32>,
+    sitemaps: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct RobotOwned {
+    rules: Vec<(String, bool)>,
+    delay: Option<f32>,
+    sitemaps: Vec<String>,
+}
+
+/// Serialize the rules (as given by [Robot::rules]), delay, and sitemaps.
+///
+/// Requires the `serde` feature.
+impl Serialize for Robot {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        RobotRef {
+            rules: self.rules().collect(),
+            delay: self.delay,
+            sitemaps: &self.sitemaps,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserialize a Robot by recompiling the rules as in [Robot::from_rules].
+///
+/// Requires the `serde` feature.
+impl<'de> Deserialize<'de> for Robot {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let robot = RobotOwned::deserialize(deserializer)?;
+        Robot::from_rules(robot.rules, robot.delay, robot.sitemaps)
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,190 @@
+use bstr::ByteSlice;
+
+use crate::parser::{lines_with_raw, robots_txt_parse_with_raw};
+use crate::{
+    editor_line_numbers, replace_nulls, Error, Line, Robot, RobotBuilder,
+    DEFAULT_MAX_BYTES,
+};
+
+// Line endings as seen by the parser (nulls are treated as newlines)
+fn is_line_end(c: &u8) -> bool {
+    matches!(c, b'\n' | b'\r' | 0)
+}
+
+fn parse_error(e: nom::Err<nom::error::Error<&[u8]>>) -> anyhow::Error {
+    anyhow::Error::new(Error::Parse).context(e.to_string())
+}
+
+/// Parse `robots.txt` fed in chunks, such as from a streamed response.
+///
+/// Complete lines are parsed as they arrive and only the partial line
+/// trailing the latest chunk is held as received. Blank lines and comments
+/// don't affect the result so aren't kept, hence padding a response costs no
+/// memory. As with [RobotBuilder::max_bytes] input past the limit is ignored.
+/// The result is the same as giving the whole response to [Robot::new].
+///
+/// ```rust
+/// use texting_robots::RobotParser;
+///
+/// let mut parser = RobotParser::new();
+/// parser.feed(b"User-Agent: Ferris\nDisa");
+/// parser.feed(b"llow: /rust\nAllow: /rust/o");
+/// parser.feed(b"cean");
+/// let r = parser.build("Ferris").unwrap();
+/// assert_eq!(r.allowed("/rust"), false);
+/// assert_eq!(r.allowed("/rust/ocean"), true);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RobotParser {
+    // The raw text of the complete lines kept (with nulls replaced)
+    lines: Vec<u8>,
+    // The line number editors show for each of the kept lines
+    numbers: Vec<usize>,
+    partial: Vec<u8>,
+    // The line number of the partial line
+    number: usize,
+    // Bytes of input taken so far towards max_bytes
+    taken: usize,
+    max_bytes: usize,
+    // Whether input past max_bytes was seen and discarded
+    exceeded: bool,
+    // The parser error for complete lines which would fail Robot::new
+    error: Option<String>,
+}
+
+impl Default for RobotParser {
+    fn default() -> Self {
+        RobotParser::with_max_bytes(DEFAULT_MAX_BYTES)
+    }
+}
+
+impl RobotParser {
+    pub fn new() -> Self {
+        RobotParser::default()
+    }
+
+    /// Start a parser ignoring any input past `max` bytes.
+    ///
+    /// See [RobotBuilder::max_bytes].
+    pub fn with_max_bytes(max: usize) -> Self {
+        RobotParser {
+            lines: vec![],
+            numbers: vec![],
+            partial: vec![],
+            number: 1,
+            taken: 0,
+            max_bytes: max,
+            exceeded: false,
+            error: None,
+        }
+    }
+
+    /// Add the next chunk of `robots.txt`.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.exceeded {
+            return;
+        }
+        let room = self.max_bytes - self.taken;
+        if bytes.len() > room {
+            self.exceeded = true;
+        }
+        let bytes = &bytes[..bytes.len().min(room)];
+        // Any BOM is only removed from the start of the input
+        let at_start = self.taken == self.partial.len();
+        self.taken += bytes.len();
+        self.partial.extend_from_slice(bytes);
+
+        // A trailing "\r" may yet be followed by the "\n" ending the same line
+        // unless the limit means nothing more follows
+        let end = match self.exceeded {
+            true => self.partial.len(),
+            false => {
+                let returns =
+                    self.partial.iter().rev().take_while(|&&c| c == b'\r');
+                self.partial.len() - returns.count()
+            }
+        };
+        if let Some(idx) = self.partial[..end].iter().rposition(is_line_end) {
+            let complete: Vec<u8> = self.partial.drain(..=idx).collect();
+            self.keep_lines(&complete, at_start);
+        }
+        // A partial line cut short by the limit is ignored as in Robot::new
+        if self.exceeded {
+            self.partial.clear();
+        }
+    }
+
+    // Parse the complete lines keeping only those which may affect the Robot
+    fn keep_lines(&mut self, complete: &[u8], at_start: bool) {
+        if self.error.is_some() {
+            return;
+        }
+        let txt = replace_nulls(complete);
+        let parsed = match at_start {
+            true => robots_txt_parse_with_raw(&txt),
+            false => lines_with_raw(&txt),
+        };
+        let raw_lines = match parsed {
+            Ok((_, raw_lines)) => raw_lines,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+        let numbers = editor_line_numbers(complete, &raw_lines, 0);
+        for ((raw, line), number) in raw_lines.iter().zip(&numbers) {
+            let content = raw.trim();
+            if content.is_empty() {
+                continue;
+            }
+            // A comment is still content when checking for an HTML page
+            match line {
+                Line::Raw(_) if content.starts_with(b"#") => {
+                    self.lines.extend_from_slice(b"#\n");
+                }
+                _ => self.lines.extend_from_slice(raw),
+            }
+            self.numbers.push(self.number + number - 1);
+        }
+        // The next line follows the last unless that ended with a null
+        if let Some(last) = numbers.last() {
+            self.number += last - 1;
+            if complete.last() != Some(&0) {
+                self.number += 1;
+            }
+        }
+    }
+
+    /// The number of bytes kept so far.
+    pub fn buffered_bytes(&self) -> usize {
+        self.lines.len() + self.partial.len()
+    }
+
+    /// Construct a Robot for the given agent from the input fed so far.
+    ///
+    /// The parser isn't consumed so Robots for several agents can be built.
+    ///
+    /// # Errors
+    ///
+    /// See [Robot::new].
+    pub fn build(&self, agent: &str) -> Result<Robot, anyhow::Error> {
+        if let Some(e) = &self.error {
+            return Err(anyhow::Error::new(Error::Parse).context(e.clone()));
+        }
+        let builder = RobotBuilder::new(agent).max_bytes(self.max_bytes);
+        let (_, mut raw_lines) =
+            lines_with_raw(&self.lines).map_err(parse_error)?;
+        // The partial line holds no null as that would have ended it
+        let at_start = self.taken == self.partial.len();
+        let (_, partial) = match at_start {
+            true => robots_txt_parse_with_raw(&self.partial),
+            false => lines_with_raw(&self.partial),
+        }
+        .map_err(parse_error)?;
+        // Only a lone "\r" can end a line within the partial line
+        let mut numbers = self.numbers.clone();
+        numbers.extend((0..partial.len()).map(|idx| self.number + idx));
+        raw_lines.extend(partial);
+        Robot::from_raw_lines(agent, raw_lines, &numbers, 0, &builder.options)
+    }
+}
@@ -0,0 +1,45 @@
+use super::Robot;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_charset_latin1() {
+        let txt = b"User-Agent: *\nDisallow: /caf\xe9\n\
+            Sitemap: https://example.com/caf\xe9.xml";
+        let r = Robot::new_with_charset("BobBot", txt, "latin1").unwrap();
+        assert!(!r.allowed("/café"));
+        assert!(!r.allowed("/caf%C3%A9"));
+        assert!(r.allowed("/caf"));
+        assert_eq!(r.sitemaps, ["https://example.com/café.xml"]);
+
+        // Without the charset the invalid UTF-8 sitemap is dropped
+        let r = Robot::new("BobBot", txt).unwrap();
+        assert!(r.sitemaps.is_empty());
+    }
+
+    #[test]
+    fn test_charset_shift_jis() {
+        // "/日本" in Shift_JIS
+        let txt = b"User-Agent: *\nDisallow: /\x93\xfa\x96\x7b";
+        let r = Robot::new_with_charset("BobBot", txt, " Shift_JIS ").unwrap();
+        assert!(!r.allowed("/日本/"));
+        assert!(r.allowed("/"));
+    }
+
+    #[test]
+    fn test_charset_utf8_and_unknown() {
+        let txt = "User-Agent: *\nDisallow: /café".as_bytes();
+        for charset in ["utf-8", "UTF8", "not-a-charset", ""] {
+            let r = Robot::new_with_charset("BobBot", txt, charset).unwrap();
+            assert!(!r.allowed("/café"), "Failed on {}", charset);
+        }
+
+        // A byte order mark takes precedence over the declared charset
+        let txt = "\u{feff}User-Agent: *\nDisallow: /café".as_bytes();
+        let r = Robot::new_with_charset("BobBot", txt, "latin1").unwrap();
+        assert!(!r.allowed("/café"));
+    }
+}
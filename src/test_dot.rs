@@ -0,0 +1,46 @@
+use super::Robot;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_dot_contains_rules_in_precedence() {
+        let txt = "User-Agent: *
+        Disallow: /
+        Allow: /ocean
+        Disallow: /ocean\\deep
+        Allow: /*.html$";
+        let r = Robot::new("BobBot", txt.as_bytes()).unwrap();
+        let dot = r.to_dot();
+
+        assert!(dot.starts_with("digraph robots {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(dot.matches('{').count(), 1);
+        assert_eq!(dot.matches('}').count(), 1);
+        // Longest patterns take precedence and appear first
+        let expected = [
+            "r0 [label=\"Disallow: /ocean\\\\deep\", fillcolor=lightcoral];",
+            "r1 [label=\"Allow: /*.html$\", fillcolor=palegreen];",
+            "r2 [label=\"Allow: /ocean\", fillcolor=palegreen];",
+            "r3 [label=\"Disallow: /\", fillcolor=lightcoral];",
+            "r0 -> r1;",
+            "r1 -> r2;",
+            "r2 -> r3;",
+        ];
+        for line in expected {
+            assert!(dot.contains(line), "Missing {} in {}", line, dot);
+        }
+        assert!(!dot.contains("r3 -> "));
+    }
+
+    #[test]
+    fn test_dot_empty_robot() {
+        let r = Robot::new("BobBot", b"").unwrap();
+        let dot = r.to_dot();
+        assert!(dot.starts_with("digraph robots {\n"));
+        assert!(!dot.contains("->"));
+        assert!(!dot.contains("label"));
+    }
+}
@@ -0,0 +1,52 @@
+use super::{RobotsFetchPolicy, StatusAction};
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_fetch_policy_status_boundaries() {
+        let policy = RobotsFetchPolicy::default();
+        for (code, expected) in [
+            (199, StatusAction::DenyAll),
+            (200, StatusAction::Parse),
+            (204, StatusAction::Parse),
+            (299, StatusAction::Parse),
+            (300, StatusAction::FollowRedirect),
+            (301, StatusAction::FollowRedirect),
+            (399, StatusAction::FollowRedirect),
+            (400, StatusAction::AllowAll),
+            (401, StatusAction::AllowAll),
+            (403, StatusAction::AllowAll),
+            (404, StatusAction::AllowAll),
+            (428, StatusAction::AllowAll),
+            (429, StatusAction::RetryAfter),
+            (430, StatusAction::AllowAll),
+            (499, StatusAction::AllowAll),
+            (500, StatusAction::DenyAll),
+            (503, StatusAction::DenyAll),
+            (599, StatusAction::DenyAll),
+            (600, StatusAction::DenyAll),
+            (0, StatusAction::DenyAll),
+        ] {
+            assert_eq!(policy.interpret_status(code), expected, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_fetch_policy_redirects() {
+        let policy = RobotsFetchPolicy::default();
+        assert_eq!(policy.max_redirects, 5);
+        for hop in 1..=5 {
+            assert!(policy.should_follow_redirect(hop));
+        }
+        assert!(!policy.should_follow_redirect(6));
+
+        let policy = RobotsFetchPolicy::new(0);
+        assert!(!policy.should_follow_redirect(1));
+        let policy = RobotsFetchPolicy::new(1);
+        assert!(policy.should_follow_redirect(1));
+        assert!(!policy.should_follow_redirect(2));
+    }
+}
@@ -0,0 +1,62 @@
+use super::ffi::{robot_allowed, robot_delay, robot_free, robot_new};
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use core::ptr;
+
+    #[test]
+    fn test_ffi_robot_lifecycle() {
+        let txt =
+            b"User-Agent: FerrisBot\nDisallow: /secret\nCrawl-Delay: 2.5";
+        unsafe {
+            let robot =
+                robot_new(c"FerrisBot".as_ptr(), txt.as_ptr(), txt.len());
+            assert!(!robot.is_null());
+            assert!(!robot_allowed(robot, c"/secret/file".as_ptr()));
+            assert!(!robot_allowed(
+                robot,
+                c"https://example.com/secret".as_ptr()
+            ));
+            assert!(robot_allowed(robot, c"/public".as_ptr()));
+
+            let mut delay = 0.0;
+            assert!(robot_delay(robot, &mut delay));
+            assert_eq!(delay, 2.5);
+            robot_free(robot);
+
+            // Without a delay the output is left untouched
+            let robot = robot_new(c"BobBot".as_ptr(), ptr::null(), 0);
+            assert!(!robot.is_null());
+            let mut delay = -1.0;
+            assert!(!robot_delay(robot, &mut delay));
+            assert_eq!(delay, -1.0);
+            assert!(robot_allowed(robot, c"/secret".as_ptr()));
+            robot_free(robot);
+        }
+    }
+
+    #[test]
+    fn test_ffi_invalid_arguments() {
+        let txt = b"Disallow: /";
+        unsafe {
+            assert!(robot_new(ptr::null(), txt.as_ptr(), txt.len()).is_null());
+            assert!(robot_new(c"BobBot".as_ptr(), ptr::null(), 1).is_null());
+            let agent = c"Bob\xffBot".as_ptr();
+            assert!(robot_new(agent, txt.as_ptr(), txt.len()).is_null());
+
+            assert!(!robot_allowed(ptr::null(), c"/".as_ptr()));
+            assert!(!robot_delay(ptr::null(), &mut 0.0));
+            robot_free(ptr::null_mut());
+
+            let robot = robot_new(c"BobBot".as_ptr(), txt.as_ptr(), txt.len());
+            assert!(!robot_allowed(robot, ptr::null()));
+            assert!(!robot_delay(robot, ptr::null_mut()));
+            // Invalid UTF-8 in the URL is still checked
+            assert!(!robot_allowed(robot, c"/\xff".as_ptr()));
+            robot_free(robot);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use super::RobotRegex;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_minregex_star_crosses_slashes() {
+        // Each pattern exercises one of the matching paths:
+        // starred (match_stars), anchored (prefix / suffix), and full regex
+        for (pattern, is_regex) in
+            [("/a*b", false), ("/a*b$", false), ("/a*x*b$", true)]
+        {
+            let r = RobotRegex::new(pattern).unwrap();
+            assert_eq!(r.is_regex(), is_regex, "Failed on {}", pattern);
+            assert!(r.is_match("/axb"), "Failed on {}", pattern);
+            assert!(r.is_match("/a/x/b"), "Failed on {}", pattern);
+            assert!(r.is_match("/a/1/x/2/b"), "Failed on {}", pattern);
+            assert!(r.is_match("/a/x/y/z/b"), "Failed on {}", pattern);
+            assert!(!r.is_match("/x/a/x/b"), "Failed on {}", pattern);
+        }
+    }
+
+    #[test]
+    fn test_minregex_dollar_only_anchors_at_end() {
+        let r = RobotRegex::new("/foo$bar").unwrap();
+        assert!(!r.is_regex());
+        assert!(r.is_match("/foo$bar"));
+        assert!(r.is_match("/foo$bar/baz"));
+        assert!(!r.is_match("/foo"));
+        assert!(!r.is_match("/foobar"));
+
+        let r = RobotRegex::new("/foo$").unwrap();
+        assert!(r.is_match("/foo"));
+        assert!(!r.is_match("/foo/"));
+        assert!(!r.is_match("/foo$"));
+        assert!(!r.is_match("/foobar"));
+
+        // Each matching path treats a "$" before the end literally
+        for pattern in ["/a$b*c", "/a$b*c$", "/a$b*c*d$", "/a$b$"] {
+            let r = RobotRegex::new(pattern).unwrap();
+            let text = pattern.trim_end_matches('$').replace('*', "");
+            assert!(r.is_match(&text), "Failed on {}", pattern);
+            assert!(!r.is_match("/ab/c/d"), "Failed on {}", pattern);
+        }
+    }
+
+    #[test]
+    fn test_minregex_match_stars_exhaustive() {
+        // Every short pattern and text over a small alphabet is compared
+        // against the equivalent regex to ensure no slicing panics or mismatches
+        fn all_strings(alphabet: &[char], max_len: usize) -> Vec<String> {
+            let mut all = vec![String::new()];
+            let mut last = vec![String::new()];
+            for _ in 0..max_len {
+                last = last
+                    .iter()
+                    .flat_map(|s| {
+                        alphabet.iter().map(move |c| format!("{}{}", s, c))
+                    })
+                    .collect();
+                all.extend(last.iter().cloned());
+            }
+            all
+        }
+
+        let r = RobotRegex::new("/").unwrap();
+        let texts = all_strings(&['a', 'b', '*'], 4);
+        for pattern in all_strings(&['a', 'b', '*'], 5) {
+            let reference = pattern
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*");
+            let reference =
+                regex::Regex::new(&format!("^{}", reference)).unwrap();
+            for text in &texts {
+                assert_eq!(
+                    r.match_stars(pattern.as_bytes(), text.as_bytes()),
+                    reference.is_match(text),
+                    "Failed on {} against {}",
+                    pattern,
+                    text
+                );
+            }
+        }
+
+        for (pattern, text) in
+            [("*a*a*", "a"), ("a*a", "a"), ("a*", ""), ("/a*b", "*x/ab")]
+        {
+            assert!(!r.match_stars(pattern.as_bytes(), text.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_minregex_many_stars_anchored() {
+        // Beyond the limit the anchor is checked without compiling a regex
+        let pattern = "/a*b*c*d*e*f*g*h*i*z$";
+        let r = RobotRegex::new(pattern).unwrap();
+        assert!(!r.is_regex());
+        assert!(!RobotRegex::requires_regex(pattern));
+        assert!(r.is_match("/abcdefghiz"));
+        assert!(r.is_match("/a/b/c/d/e/f/g/h/i/z"));
+        assert!(r.is_match("/a-z-b-c-d-e-f-g-h-i-zz"));
+        assert!(!r.is_match("/abcdefghiz/"));
+        assert!(!r.is_match("/abcdefghz"));
+        assert!(!r.is_match("/bacdefghiz"));
+
+        // A regex exceeding the size limit falls back rather than erroring
+        let part = "x".repeat(10_000);
+        let pattern = format!("/{}*{}*{}$", part, part, part);
+        let r = RobotRegex::new(&pattern).unwrap();
+        assert!(!r.is_regex());
+        assert!(r.is_match(&format!("/{}/{}/{}", part, part, part)));
+        assert!(!r.is_match(&format!("/{}/{}/{}/", part, part, part)));
+    }
+
+    #[test]
+    fn test_minregex_scan_limit() {
+        // Many short parts against a long text finish in a single pass
+        let pattern = format!("/{}*b", "*a".repeat(1000));
+        let text = format!("/{}", "a".repeat(100_000));
+        let r = RobotRegex::new(&pattern).unwrap();
+        assert!(!r.is_match(&text));
+        assert!(r.is_match(&format!("{}b", text)));
+
+        // Beyond the limit the text is taken as matching only if asked
+        let r =
+            RobotRegex::new(&pattern).unwrap().with_scan_limit(2000, false);
+        assert!(!r.is_match(&format!("{}b", text)));
+        assert!(r.is_match(&format!("/{}b", "a".repeat(1500))));
+        let r = RobotRegex::new(&pattern).unwrap().with_scan_limit(2000, true);
+        assert!(r.is_match(&text));
+        assert_eq!(r.matches_prefix_len(&text), Some(text.len()));
+        assert!(!r.is_match(&format!("/{}", "a".repeat(1500))));
+    }
+
+    #[test]
+    fn test_minregex_match_stars_anchored_exhaustive() {
+        // As test_minregex_match_stars_exhaustive but matching to the end
+        let r = RobotRegex::new("/").unwrap();
+        let alphabet = ['a', 'b', '*'];
+        let mut strings = vec![String::new()];
+        for _ in 0..5 {
+            let longer: Vec<String> = strings
+                .iter()
+                .filter(|s| s.len() == strings.last().unwrap().len())
+                .flat_map(|s| {
+                    alphabet.iter().map(move |c| format!("{}{}", s, c))
+                })
+                .collect();
+            strings.extend(longer);
+        }
+        for pattern in &strings {
+            let reference = pattern
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*");
+            let reference =
+                regex::Regex::new(&format!("^{}$", reference)).unwrap();
+            for text in strings.iter().filter(|s| s.len() <= 4) {
+                assert_eq!(
+                    r.match_stars_anchored(
+                        pattern.as_bytes(),
+                        text.as_bytes()
+                    ),
+                    reference.is_match(text),
+                    "Failed on {} against {}",
+                    pattern,
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_minregex_matches_prefix_len() {
+        for (pattern, text, expected) in [
+            ("/fish", "/fish/salmon", Some(5)),
+            ("/fish", "/shark/fish", None),
+            ("/fish*", "/fish/salmon", Some(5)),
+            ("/fish*.php", "/fish/a.php?q=1", Some(11)),
+            ("/*fish", "/shark/fish/tail", Some(11)),
+            ("*.php", "/a.php/b.php", Some(6)),
+            ("/fish*$", "/fishing", Some(5)),
+            ("/*.php$", "/a/b.php", Some(8)),
+            ("/a*b*c$", "/a/b/c", Some(6)),
+            ("/a*b*c$", "/a/b/c/", None),
+            ("/a*b*c*d*e*f*g*h*i*z$", "/abcdefghiz", Some(11)),
+            ("/fish$", "/fish", Some(5)),
+        ] {
+            let r = RobotRegex::new(pattern).unwrap();
+            assert_eq!(
+                r.matches_prefix_len(text),
+                expected,
+                "Failed on {} against {}",
+                pattern,
+                text
+            );
+        }
+
+        let r = RobotRegex::new_with_case("/Fish*.PHP", true).unwrap();
+        assert_eq!(r.matches_prefix_len("/fish/A.php?q"), Some(11));
+        let r = RobotRegex::new_with_case("/Fish", false).unwrap();
+        assert_eq!(r.matches_prefix_len("/fish"), None);
+    }
+
+    #[test]
+    fn test_minregex_literal_prefix_of_normalized() {
+        // The literal prefix matches at least everything the full rule does
+        for pattern in ["/a$*", "/a$**", "/a**b$", "?q", "$", "/a/*/b$"] {
+            let full = RobotRegex::new(pattern).unwrap();
+            let prefix = RobotRegex::literal_prefix(pattern, false);
+            assert_eq!(prefix.pattern(), pattern);
+            for text in ["/", "/a", "/a$", "/a$x", "/ab", "/a/x/b", "/x?q"] {
+                if full.is_match(text) {
+                    assert!(
+                        prefix.is_match(text),
+                        "Failed on {} against {}",
+                        pattern,
+                        text
+                    );
+                }
+            }
+        }
+        let prefix = RobotRegex::literal_prefix("/a**b$", false);
+        assert!(prefix.is_match("/a"));
+        assert!(!prefix.is_match("/b"));
+    }
+
+    #[test]
+    fn test_minregex_double_star_equivalent() {
+        // Some files use "**" to mean "across slashes" but "*" already does
+        for (single, double) in [
+            ("/a*b", "/a**b"),
+            ("/a*b$", "/a**b$"),
+            ("/a*b*c$", "/a**b**c$"),
+            ("*/private/", "**/private/"),
+        ] {
+            let single = RobotRegex::new(single).unwrap();
+            let double = RobotRegex::new(double).unwrap();
+            for text in [
+                "/ab",
+                "/abc",
+                "/a/b",
+                "/a/x/b/c",
+                "/a/x/b/c/d",
+                "/x/private/",
+                "/x/y/private/z",
+                "/b",
+            ] {
+                assert_eq!(
+                    single.is_match(text),
+                    double.is_match(text),
+                    "Failed on {}",
+                    text
+                );
+            }
+        }
+    }
+}
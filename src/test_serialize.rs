@@ -0,0 +1,55 @@
+use super::Robot;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let txt = "User-Agent: *
+        Disallow: /
+        Allow: /ocean
+        Disallow: /ocean/*.gif$
+        Disallow: /ツ
+        Crawl-Delay: 2.5
+        Sitemap: https://example.com/sitemap.xml";
+        let r = Robot::new("BobBot", txt.as_bytes()).unwrap();
+        let json = serde_json::to_string(&r).unwrap();
+        let copy: Robot = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            copy.rules().collect::<Vec<_>>(),
+            r.rules().collect::<Vec<_>>()
+        );
+        assert_eq!(copy.delay, Some(2.5));
+        assert_eq!(copy.sitemaps, r.sitemaps);
+        for url in
+            ["/", "/ocean", "/ocean/fish.gif", "/ocean/fish.gif?x", "/ツ"]
+        {
+            assert_eq!(copy.allowed(url), r.allowed(url), "Failed on {}", url);
+        }
+    }
+
+    #[test]
+    fn test_serialize_format() {
+        let r = Robot::new("BobBot", b"Disallow: /a\nAllow: /a/b").unwrap();
+        assert_eq!(
+            serde_json::to_string(&r).unwrap(),
+            r#"{"rules":[["/a",false],["/a/b",true]],"delay":null,"sitemaps":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_invalid_rule() {
+        // Rules too large for a regex no longer fail to deserialize
+        let pat = "/*".to_string() + &"A".repeat(4096) + "*B$";
+        let json = format!(
+            r#"{{"rules":[["{}",false]],"delay":null,"sitemaps":[]}}"#,
+            pat
+        );
+        let r = serde_json::from_str::<Robot>(&json).unwrap();
+        assert!(!r.allowed(&format!("/{}B", "A".repeat(4096))));
+        assert!(serde_json::from_str::<Robot>(r#"{"rules":[]}"#).is_err());
+    }
+}
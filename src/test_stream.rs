@@ -0,0 +1,95 @@
+use super::{Robot, RobotBuilder, RobotParser};
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_same(a: &Robot, b: &Robot) {
+        assert_eq!(
+            a.rules().collect::<Vec<_>>(),
+            b.rules().collect::<Vec<_>>()
+        );
+        assert_eq!(a.delay, b.delay);
+        assert_eq!(a.sitemaps, b.sitemaps);
+        assert_eq!(
+            a.rules_with_lines().collect::<Vec<_>>(),
+            b.rules_with_lines().collect::<Vec<_>>()
+        );
+        assert_eq!(a.warnings(), b.warnings());
+    }
+
+    #[test]
+    fn test_stream_matches_whole_input_for_any_chunking() {
+        let txt = b"\xef\xbb\xbfUser-Agent: BobBot\r\nDisallow: /fish\r\n\
+            # Comment\r\r\n\r\nAllow: /fish/salmon\rCrawl-Delay: 2\n\x00\
+            User-Agent: *\nDisallow: /\nDisalow: /typo\r\r\
+            Sitemap: https://example.com/s.xml\r";
+        let expected = Robot::new("BobBot", txt).unwrap();
+        for size in 1..=txt.len() {
+            let mut parser = RobotParser::new();
+            for chunk in txt.chunks(size) {
+                parser.feed(chunk);
+            }
+            assert!(parser.buffered_bytes() < txt.len());
+            assert_same(&parser.build("BobBot").unwrap(), &expected);
+        }
+
+        let parser = RobotParser::new();
+        assert!(parser.build("BobBot").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stream_bounded_by_max_bytes() {
+        let txt = b"Disallow: /fish\nDisallow: /shark\nDisallow: /whale";
+        for max in 0..=txt.len() + 1 {
+            let expected =
+                RobotBuilder::new("BobBot").max_bytes(max).build(txt).unwrap();
+            for size in [1, 3, 7, txt.len()] {
+                let mut parser = RobotParser::with_max_bytes(max);
+                for chunk in txt.chunks(size) {
+                    parser.feed(chunk);
+                }
+                assert!(parser.buffered_bytes() <= max);
+                let r = parser.build("BobBot").unwrap();
+                assert_same(&r, &expected);
+            }
+        }
+
+        // An adversarially large response is never held in memory
+        let mut parser = RobotParser::with_max_bytes(1024);
+        let line = b"Disallow: /fish\n".repeat(100);
+        for _ in 0..1000 {
+            parser.feed(&line);
+        }
+        assert!(parser.buffered_bytes() <= 1024);
+        assert_eq!(parser.build("BobBot").unwrap().rule_count(), 64);
+    }
+
+    #[test]
+    fn test_stream_keeps_only_lines_with_directives() {
+        // Padding with blank lines and comments isn't held in memory
+        let mut parser = RobotParser::new();
+        parser.feed(b"User-Agent: *\n");
+        for _ in 0..10_000 {
+            parser.feed(b"# Padding padding padding\n\n");
+        }
+        parser.feed(b"Disallow: /fish\nFoo: bar");
+        assert!(parser.buffered_bytes() < 1024 * 64);
+        let r = parser.build("BobBot").unwrap();
+        assert_eq!(
+            r.rules_with_lines().collect::<Vec<_>>(),
+            [(20_002, "/fish", false)]
+        );
+        assert_eq!(r.warnings()[0].line, 20_003);
+
+        // A comment before an HTML page is still content
+        let txt = b"# Comment\n<html>\n<body>\n";
+        let mut parser = RobotParser::new();
+        parser.feed(txt);
+        assert_eq!(
+            parser.build("BobBot").unwrap().warnings(),
+            Robot::new("BobBot", txt).unwrap().warnings()
+        );
+    }
+}